@@ -3,7 +3,7 @@ use fjadra::{Center, Link, ManyBody, Node, SimulationBuilder};
 use fjadra_data::miserables;
 
 fn bench_simulation(c: &mut Criterion) {
-    let graph = miserables::Graph::load().unwrap();
+    let graph = miserables::Graph::load().expect("data should be available");
 
     c.bench_function("miserables (init)", |b| {
         b.iter(|| {
@@ -8,7 +8,7 @@ fn random_tree(c: &mut Criterion) {
     let mut group = c.benchmark_group("random_tree");
     group.sample_size(10);
 
-    for size in [50, 100, 1_000, 10_000].iter() {
+    for size in &[50, 100, 1_000, 10_000] {
         group.bench_with_input(BenchmarkId::from_parameter(size), size, |b, &size| {
             b.iter(|| {
                 let mut simulation = SimulationBuilder::default()
@@ -22,7 +22,7 @@ fn main() -> anyhow::Result<()> {
         .build(nodes.iter().map(|_| Node::default()))
         .add_force(
             "link",
-            Link::new(edges.clone().into_iter())
+            Link::new(edges.clone())
                 .strength(1.0)
                 .distance(60.0)
                 .iterations(10),
@@ -27,6 +27,8 @@ pub struct SchemeCategory10;
 /// Eventually, we should read / store this in a binary format, potentially using `const fn`.
 const CATEGORY_10: &str = "1f77b4ff7f0e2ca02cd627289467bd8c564be377c27f7f7fbcbd2217becf";
 
+// `allow`: The palette is a constant, so parsing it can't fail.
+#[allow(clippy::fallible_impl_from, clippy::unwrap_used)]
 impl From<SchemeCategory10> for ScaleOrdinal<10> {
     fn from(_: SchemeCategory10) -> Self {
         let mut colors = [Color { r: 0, g: 0, b: 0 }; 10];
@@ -0,0 +1,488 @@
+//! Force-directed edge bundling (FDEB) on top of the output of a [`Simulation`](crate::Simulation).
+//!
+//! Each edge is subdivided into control points, which are then attracted to the corresponding
+//! control points of compatible edges. The implementation follows Holten and van Wijk,
+//! "Force-Directed Edge Bundling for Graph Visualization" (2009).
+
+use crate::{
+    force::{particle::Particle, Force, ForceBuilder},
+    quadtree::{Entry, Quadtree, Visit},
+    Link, Node, SimulationBuilder,
+};
+
+/// Bundles the edges of a graph, given the final positions of its nodes.
+#[derive(Clone, Debug)]
+pub struct EdgeBundling {
+    links: Vec<(usize, usize)>,
+    cycles: usize,
+    subdivisions: usize,
+    subdivision_rate: usize,
+    iterations: usize,
+    iterations_rate: f64,
+    step: f64,
+    stiffness: f64,
+    compatibility_threshold: f64,
+}
+
+impl EdgeBundling {
+    /// Creates the bundling for edges given as pairs of indices into the positions that are
+    /// passed to [`bundle`](Self::bundle).
+    pub fn new(links: impl IntoIterator<Item = (usize, usize)>) -> Self {
+        Self {
+            links: links.into_iter().collect(),
+            cycles: 6,
+            subdivisions: 1,
+            subdivision_rate: 2,
+            iterations: 90,
+            iterations_rate: 2.0 / 3.0,
+            step: 0.1,
+            stiffness: 0.1,
+            compatibility_threshold: 0.6,
+        }
+    }
+
+    /// Sets the number of cycles. After each cycle the edges are subdivided further.
+    pub fn cycles(mut self, cycles: usize) -> Self {
+        self.cycles = cycles;
+        self
+    }
+
+    /// Sets the number of control points per edge in the first cycle.
+    pub fn subdivisions(mut self, subdivisions: usize) -> Self {
+        self.subdivisions = subdivisions;
+        self
+    }
+
+    /// Sets the factor by which the number of control points grows after each cycle.
+    pub fn subdivision_rate(mut self, subdivision_rate: usize) -> Self {
+        self.subdivision_rate = subdivision_rate;
+        self
+    }
+
+    /// Sets the number of iterations of the first cycle.
+    pub fn iterations(mut self, iterations: usize) -> Self {
+        self.iterations = iterations;
+        self
+    }
+
+    /// Sets the factor by which the number of iterations changes after each cycle.
+    pub fn iterations_rate(mut self, iterations_rate: f64) -> Self {
+        self.iterations_rate = iterations_rate;
+        self
+    }
+
+    /// Sets the initial step size, which is used as the alpha value of the simulation.
+    pub fn step(mut self, step: f64) -> Self {
+        self.step = step;
+        self
+    }
+
+    /// Sets the global spring constant that keeps edges from bending too much.
+    pub fn stiffness(mut self, stiffness: f64) -> Self {
+        self.stiffness = stiffness;
+        self
+    }
+
+    /// Sets the minimal compatibility that is required for two edges to attract each other.
+    pub fn compatibility_threshold(mut self, threshold: f64) -> Self {
+        self.compatibility_threshold = threshold;
+        self
+    }
+
+    /// Computes a polyline for each link, in the order in which the links were given.
+    ///
+    /// The polylines start and end at the positions of the source and target nodes. Self-loops
+    /// and other edges of zero length have no direction to bundle along, so they stay straight.
+    pub fn bundle(&self, positions: impl IntoIterator<Item = [f64; 2]>) -> Vec<Vec<[f64; 2]>> {
+        let positions = positions.into_iter().collect::<Vec<_>>();
+
+        let mut polylines = self
+            .links
+            .iter()
+            .map(|&(s, t)| vec![positions[s], positions[t]])
+            .collect::<Vec<_>>();
+
+        let degenerate = self
+            .links
+            .iter()
+            .map(|&(s, t)| positions[s] == positions[t])
+            .collect::<Vec<_>>();
+        let pairs = self.compatible_pairs(&positions);
+
+        let mut subdivisions = self.subdivisions;
+        let mut step = self.step;
+        let mut iterations = self.iterations as f64;
+
+        for _ in 0..self.cycles {
+            for polyline in &mut polylines {
+                *polyline = subdivide(polyline, subdivisions);
+            }
+
+            let stride = subdivisions + 2;
+            let nodes = polylines
+                .iter()
+                .zip(&degenerate)
+                .flat_map(|(polyline, &fixed)| {
+                    let last = polyline.len() - 1;
+                    polyline.iter().enumerate().map(move |(i, &[x, y])| {
+                        if i == 0 || i == last || fixed {
+                            Node::default().fixed_position(x, y)
+                        } else {
+                            Node::default().position(x, y)
+                        }
+                    })
+                });
+
+            let springs = (0..polylines.len())
+                .filter(|&e| !degenerate[e])
+                .flat_map(|e| (0..stride - 1).map(move |i| (e * stride + i, e * stride + i + 1)));
+            let strengths = self
+                .links
+                .iter()
+                .zip(&degenerate)
+                .filter(|(_, &degenerate)| !degenerate)
+                .flat_map(|(&(s, t), _)| {
+                    let [sx, sy] = positions[s];
+                    let [tx, ty] = positions[t];
+                    let length = (tx - sx).hypot(ty - sy);
+                    let k = self.stiffness / (length * (subdivisions + 1) as f64);
+                    std::iter::repeat(k).take(stride - 1)
+                })
+                .collect::<Vec<_>>();
+
+            // The step size is constant within a cycle, so there is no decay.
+            let mut simulation = SimulationBuilder::default()
+                .with_alpha(step)
+                .with_alpha_decay(0.0)
+                .with_velocity_decay(0.0)
+                .build(nodes)
+                .add_force(
+                    "springs",
+                    Link::new(springs)
+                        .distance(0.0)
+                        .strength(move |_: &_, i| strengths[i]),
+                )
+                .add_force(
+                    "electrostatic",
+                    Bundle {
+                        pairs: pairs.clone(),
+                        stride,
+                    },
+                );
+
+            simulation.tick(iterations.round() as usize);
+
+            let points = simulation.positions().collect::<Vec<_>>();
+            for (polyline, chunk) in polylines.iter_mut().zip(points.chunks(stride)) {
+                polyline.copy_from_slice(chunk);
+            }
+
+            subdivisions *= self.subdivision_rate;
+            step /= 2.0;
+            iterations *= self.iterations_rate;
+        }
+
+        polylines
+    }
+
+    /// Finds all pairs of edges whose compatibility exceeds the threshold.
+    ///
+    /// Edges can only be compatible if their midpoints are close, relative to their lengths,
+    /// so we use a quadtree on the midpoints to prune the candidates.
+    fn compatible_pairs(&self, positions: &[[f64; 2]]) -> Vec<Pair> {
+        let edges = self
+            .links
+            .iter()
+            .map(|&(s, t)| Segment::new(positions[s], positions[t]))
+            .collect::<Vec<_>>();
+
+        let max_length = edges.iter().map(Segment::length).fold(0.0, f64::max);
+
+        let mut tree = Quadtree::<(), usize>::from_points_with(
+            0..edges.len(),
+            |&i| edges[i].midpoint[0],
+            |&i| edges[i].midpoint[1],
+        );
+
+        let mut pairs = Vec::new();
+        for (p, edge) in edges.iter().enumerate() {
+            if edge.length() == 0.0 {
+                continue;
+            }
+
+            // The position compatibility drops below the threshold beyond this distance.
+            let radius =
+                (edge.length() + max_length) / 2.0 * (1.0 / self.compatibility_threshold - 1.0);
+            let [mx, my] = edge.midpoint;
+
+            tree.visit(|quad| {
+                let extent = quad.extent();
+                if extent.x0 > mx + radius
+                    || extent.x1 < mx - radius
+                    || extent.y0 > my + radius
+                    || extent.y1 < my - radius
+                {
+                    return Visit::Skip;
+                }
+
+                if let Entry::Leaf { data, others, .. } = quad.inner() {
+                    for &q in std::iter::once(data).chain(others.unwrap_or_default()) {
+                        if q <= p {
+                            continue;
+                        }
+                        let compatibility = edge.compatibility(&edges[q]);
+                        if compatibility >= self.compatibility_threshold {
+                            pairs.push(Pair {
+                                p,
+                                q,
+                                compatibility,
+                                reversed: edge.direction(&edges[q]) < 0.0,
+                            });
+                        }
+                    }
+                }
+                Visit::Continue
+            });
+        }
+        pairs
+    }
+}
+
+/// Resamples a polyline into `n` control points of equal arc length, plus the endpoints.
+fn subdivide(polyline: &[[f64; 2]], n: usize) -> Vec<[f64; 2]> {
+    let lengths = polyline
+        .windows(2)
+        .map(|w| (w[1][0] - w[0][0]).hypot(w[1][1] - w[0][1]))
+        .collect::<Vec<_>>();
+    let total = lengths.iter().sum::<f64>();
+
+    let first = polyline[0];
+    let last = polyline[polyline.len() - 1];
+
+    let mut result = Vec::with_capacity(n + 2);
+    result.push(first);
+
+    let segment = total / (n + 1) as f64;
+    let mut current = 0;
+    let mut offset = 0.0;
+    for i in 1..=n {
+        let target = segment * i as f64;
+        while current < lengths.len() - 1 && offset + lengths[current] < target {
+            offset += lengths[current];
+            current += 1;
+        }
+
+        let [ax, ay] = polyline[current];
+        let [bx, by] = polyline[current + 1];
+        let t = if lengths[current] > 0.0 {
+            ((target - offset) / lengths[current]).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        result.push([ax + (bx - ax) * t, ay + (by - ay) * t]);
+    }
+
+    result.push(last);
+    result
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Segment {
+    source: [f64; 2],
+    target: [f64; 2],
+    midpoint: [f64; 2],
+}
+
+impl Segment {
+    fn new(source: [f64; 2], target: [f64; 2]) -> Self {
+        Self {
+            source,
+            target,
+            midpoint: [(source[0] + target[0]) / 2.0, (source[1] + target[1]) / 2.0],
+        }
+    }
+
+    fn vector(&self) -> [f64; 2] {
+        [
+            self.target[0] - self.source[0],
+            self.target[1] - self.source[1],
+        ]
+    }
+
+    fn length(&self) -> f64 {
+        let [x, y] = self.vector();
+        x.hypot(y)
+    }
+
+    fn direction(&self, other: &Self) -> f64 {
+        let [ax, ay] = self.vector();
+        let [bx, by] = other.vector();
+        ax * bx + ay * by
+    }
+
+    /// Computes the product of the angle, scale, position, and visibility compatibilities.
+    fn compatibility(&self, other: &Self) -> f64 {
+        let (lp, lq) = (self.length(), other.length());
+        if lp == 0.0 || lq == 0.0 {
+            return 0.0;
+        }
+
+        let angle = (self.direction(other) / (lp * lq)).abs();
+
+        let average = (lp + lq) / 2.0;
+        let scale = 2.0 / (average / lp.min(lq) + lp.max(lq) / average);
+
+        let [mpx, mpy] = self.midpoint;
+        let [mqx, mqy] = other.midpoint;
+        let position = average / (average + (mpx - mqx).hypot(mpy - mqy));
+
+        let visibility = f64::min(self.visibility(other), other.visibility(self));
+
+        angle * scale * position * visibility
+    }
+
+    /// Measures how much of `other`, projected onto the line through `self`, overlaps `self`.
+    fn visibility(&self, other: &Self) -> f64 {
+        let project = |[x, y]: [f64; 2]| {
+            let [vx, vy] = self.vector();
+            let [sx, sy] = self.source;
+            let t = ((x - sx) * vx + (y - sy) * vy) / (vx * vx + vy * vy);
+            [sx + t * vx, sy + t * vy]
+        };
+
+        let [i0x, i0y] = project(other.source);
+        let [i1x, i1y] = project(other.target);
+        let [mpx, mpy] = self.midpoint;
+        let (imx, imy) = ((i0x + i1x) / 2.0, (i0y + i1y) / 2.0);
+
+        let l = (i1x - i0x).hypot(i1y - i0y);
+        if l == 0.0 {
+            return 0.0;
+        }
+        f64::max(1.0 - 2.0 * (mpx - imx).hypot(mpy - imy) / l, 0.0)
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Pair {
+    p: usize,
+    q: usize,
+    compatibility: f64,
+    /// Whether the edges point in opposite directions, so that their control points have to
+    /// be matched in reverse order.
+    reversed: bool,
+}
+
+/// Attracts the control points of compatible edges to each other.
+struct Bundle {
+    pairs: Vec<Pair>,
+    stride: usize,
+}
+
+impl ForceBuilder for Bundle {
+    fn initialize(self, _: &[Particle]) -> Force {
+        Force::Bundle(BundleForce {
+            pairs: self.pairs,
+            stride: self.stride,
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct BundleForce {
+    pairs: Vec<Pair>,
+    stride: usize,
+}
+
+impl BundleForce {
+    pub fn force(&self, alpha: f64, particles: &mut [Particle]) {
+        // The endpoints are fixed, so we only need to look at the inner control points.
+        let inner = self.stride - 2;
+
+        let mut forces = vec![[0.0; 2]; particles.len()];
+        for pair in &self.pairs {
+            for i in 1..=inner {
+                let j = if pair.reversed {
+                    self.stride - 1 - i
+                } else {
+                    i
+                };
+                let a = pair.p * self.stride + i;
+                let b = pair.q * self.stride + j;
+
+                let x = particles[b].x - particles[a].x;
+                let y = particles[b].y - particles[a].y;
+                let l = x * x + y * y;
+                if l == 0.0 {
+                    continue;
+                }
+
+                // The electrostatic force is inversely proportional to the distance.
+                let w = pair.compatibility / l;
+                forces[a][0] += x * w;
+                forces[a][1] += y * w;
+                forces[b][0] -= x * w;
+                forces[b][1] -= y * w;
+            }
+        }
+
+        for (node, [fx, fy]) in particles.iter_mut().zip(forces) {
+            node.vx += fx * alpha;
+            node.vy += fy * alpha;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn subdivides_polylines_evenly() {
+        assert_eq!(
+            subdivide(&[[0.0, 0.0], [4.0, 0.0]], 3),
+            vec![[0.0, 0.0], [1.0, 0.0], [2.0, 0.0], [3.0, 0.0], [4.0, 0.0]]
+        );
+        assert_eq!(
+            subdivide(&[[0.0, 0.0], [2.0, 0.0], [2.0, 2.0]], 1),
+            vec![[0.0, 0.0], [2.0, 0.0], [2.0, 2.0]]
+        );
+    }
+
+    #[test]
+    fn parallel_edges_are_compatible() {
+        let a = Segment::new([0.0, 0.0], [100.0, 0.0]);
+        let b = Segment::new([0.0, 10.0], [100.0, 10.0]);
+        let c = Segment::new([0.0, 0.0], [0.0, 100.0]);
+        approx::assert_abs_diff_eq!(a.compatibility(&b), 100.0 / 110.0, epsilon = 1e-9);
+        approx::assert_abs_diff_eq!(a.compatibility(&c), 0.0, epsilon = 1e-9);
+    }
+
+    #[test]
+    fn bundles_parallel_edges() {
+        let positions = [[0.0, 0.0], [100.0, 0.0], [0.0, 10.0], [100.0, 10.0]];
+        let polylines = EdgeBundling::new([(0, 1), (3, 2)]).bundle(positions);
+
+        assert_eq!(polylines.len(), 2);
+        for (polyline, (s, t)) in polylines.iter().zip([(0, 1), (3, 2)]) {
+            assert_eq!(polyline.len(), 34);
+            assert_eq!(polyline[0], positions[s]);
+            assert_eq!(polyline[polyline.len() - 1], positions[t]);
+        }
+
+        let a = polylines[0][17];
+        let b = polylines[1][16];
+        // The edges point in opposite directions, so the middle control points are matched.
+        assert!(b[1] - a[1] < 9.0);
+        approx::assert_abs_diff_eq!(a[0], b[0], epsilon = 1e-6);
+    }
+
+    #[test]
+    fn keeps_degenerate_edges_straight() {
+        let positions = [[0.0, 0.0], [100.0, 0.0], [50.0, 5.0]];
+        let polylines = EdgeBundling::new([(0, 1), (2, 2)]).bundle(positions);
+
+        assert!(polylines.iter().flatten().flatten().all(|v| v.is_finite()));
+        assert!(polylines[1].iter().all(|&p| p == [50.0, 5.0]));
+    }
+}
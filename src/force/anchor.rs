@@ -0,0 +1,110 @@
+use super::{
+    particle::Particle,
+    simulation::{Force, ForceBuilder},
+};
+
+/// Springs each particle toward its position in a previous layout, to preserve the mental map.
+///
+/// Particles whose index is beyond the previous positions are not anchored.
+pub struct Anchor {
+    positions: Vec<[f64; 2]>,
+    strength: f64,
+    weight_fn: Box<dyn Fn(usize) -> f64>,
+}
+
+impl Anchor {
+    /// Creates the force from the output of [`Simulation::positions`](crate::Simulation::positions).
+    pub fn new(positions: impl IntoIterator<Item = [f64; 2]>) -> Self {
+        Self {
+            positions: positions.into_iter().collect(),
+            strength: 0.1,
+            weight_fn: Box::new(|_| 1.0),
+        }
+    }
+
+    pub fn strength(mut self, strength: f64) -> Self {
+        self.strength = strength;
+        self
+    }
+
+    /// Sets a weight per node, which scales the strength of its spring.
+    ///
+    /// For example, nodes whose neighborhood changed can be given a lower weight than the rest.
+    pub fn weight<F>(mut self, f: F) -> Self
+    where
+        F: Fn(usize) -> f64 + 'static,
+    {
+        self.weight_fn = Box::new(f);
+        self
+    }
+}
+
+impl ForceBuilder for Anchor {
+    fn initialize(self, particles: &[Particle]) -> Force {
+        let strengths = particles
+            .iter()
+            .map(|n| {
+                let i = n.index.into();
+                if i < self.positions.len() {
+                    self.strength * (self.weight_fn)(i)
+                } else {
+                    0.0
+                }
+            })
+            .collect();
+
+        Force::Anchor(AnchorForce {
+            positions: self.positions,
+            strengths,
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct AnchorForce {
+    positions: Vec<[f64; 2]>,
+    strengths: Vec<f64>,
+}
+
+impl AnchorForce {
+    pub fn force(&self, alpha: f64, particles: &mut [Particle]) {
+        for node in particles.iter_mut() {
+            let i = usize::from(node.index);
+            let strength = self.strengths[i];
+            if strength == 0.0 {
+                continue;
+            }
+
+            let [x, y] = self.positions[i];
+            node.vx += (x - node.x) * strength * alpha;
+            node.vy += (y - node.y) * strength * alpha;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{ManyBody, Node, SimulationBuilder};
+
+    use super::*;
+
+    #[test]
+    fn pulls_nodes_toward_previous_positions() {
+        let previous = [[-50.0, 0.0], [50.0, 0.0]];
+        let mut simulation = SimulationBuilder::default()
+            .build([
+                Node::default().position(0.0, 0.0),
+                Node::default().position(0.0, 10.0),
+                Node::default().position(10.0, 0.0),
+            ])
+            .add_force("anchor", Anchor::new(previous).weight(|i| [1.0, 0.0][i]))
+            .add_force("charge", ManyBody::new().strength(-1.0));
+
+        let positions = simulation.iter().last().unwrap();
+
+        let [x, y] = positions[0];
+        assert!((x + 50.0).hypot(y) < 5.0);
+        let [x, y] = positions[1];
+        assert!((x - 50.0).hypot(y) > 5.0);
+    }
+}
@@ -0,0 +1,138 @@
+use std::f64::consts::TAU;
+
+use crate::graph::Graph;
+
+use super::{
+    many_body::NodeFn,
+    particle::Particle,
+    simulation::{Force, ForceBuilder},
+};
+
+/// Spreads the edges that are incident to a node evenly around it.
+///
+/// Whenever two consecutive edges enclose an angle that is smaller than `2π / degree`, their
+/// endpoints receive tangential impulses that rotate them apart.
+pub struct AngularResolution {
+    links: Vec<(usize, usize)>,
+    strength: NodeFn,
+}
+
+impl AngularResolution {
+    /// Creates the force for edges given as pairs of node indices.
+    ///
+    /// Every edge counts as incident to both of its endpoints. Self-loops are ignored.
+    pub fn new(links: impl IntoIterator<Item = (usize, usize)>) -> Self {
+        Self {
+            links: links.into_iter().collect(),
+            strength: NodeFn::from(0.5),
+        }
+    }
+
+    /// Sets the strength per node, which controls how much its neighbors are spread out.
+    pub fn strength(mut self, f: impl Into<NodeFn>) -> Self {
+        self.strength = f.into();
+        self
+    }
+}
+
+impl ForceBuilder for AngularResolution {
+    fn initialize(self, particles: &[Particle]) -> Force {
+        let graph = Graph::from_links(particles.len(), self.links);
+
+        let strengths = particles
+            .iter()
+            .enumerate()
+            .map(|(i, node)| (self.strength.0)(node.index, i))
+            .collect();
+
+        Force::AngularResolution(AngularResolutionForce { graph, strengths })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct AngularResolutionForce {
+    graph: Graph,
+    strengths: Vec<f64>,
+}
+
+impl AngularResolutionForce {
+    pub fn force(&self, alpha: f64, particles: &mut [Particle]) {
+        let mut incident = Vec::new();
+
+        for center in 0..self.graph.len() {
+            let neighbors = self.graph.neighbors(center);
+            let strength = self.strengths[center];
+            if neighbors.len() < 2 || strength == 0.0 {
+                continue;
+            }
+
+            let (cx, cy) = (particles[center].x, particles[center].y);
+
+            incident.clear();
+            incident.extend(neighbors.iter().map(|&n| {
+                let node = &particles[n];
+                let (dx, dy) = (node.x - cx, node.y - cy);
+                (dy.atan2(dx), n, dx, dy)
+            }));
+            incident.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+            let ideal = TAU / incident.len() as f64;
+
+            for i in 0..incident.len() {
+                let (a, a_index, ax, ay) = incident[i];
+                let (b, b_index, bx, by) = incident[(i + 1) % incident.len()];
+
+                let gap = (b - a).rem_euclid(TAU);
+                if gap >= ideal {
+                    continue;
+                }
+
+                // Rotate `a` clockwise and `b` counter-clockwise by half of the missing angle.
+                let delta = (ideal - gap) / 2.0 * strength * alpha;
+
+                let node = &mut particles[a_index];
+                node.vx += ay * delta;
+                node.vy -= ax * delta;
+
+                let node = &mut particles[b_index];
+                node.vx -= by * delta;
+                node.vy += bx * delta;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Link, Node, SimulationBuilder};
+
+    use super::*;
+
+    #[test]
+    fn spreads_incident_edges_evenly() {
+        let links = [(0, 1), (0, 2), (0, 3)];
+        let mut simulation = SimulationBuilder::default()
+            .build([
+                Node::default().fixed_position(0.0, 0.0),
+                Node::default().position(30.0, 0.0),
+                Node::default().position(29.0, 5.0),
+                Node::default().position(29.0, -5.0),
+            ])
+            .add_force("angular", AngularResolution::new(links))
+            .add_force("link", Link::new(links));
+
+        let positions = simulation.iter().last().unwrap();
+
+        let mut angles = positions[1..]
+            .iter()
+            .map(|[x, y]| y.atan2(*x))
+            .collect::<Vec<_>>();
+        angles.sort_by(f64::total_cmp);
+
+        let ideal = TAU / 3.0;
+        for i in 0..angles.len() {
+            let gap = (angles[(i + 1) % angles.len()] - angles[i]).rem_euclid(TAU);
+            approx::assert_abs_diff_eq!(gap, ideal, epsilon = 0.05);
+        }
+    }
+}
@@ -1,13 +1,14 @@
 use crate::{
     force::particle::ParticleIndex,
     lcg::Lcg,
-    quadtree::{Entry, Quad, Quadtree, Visit},
+    quadtree::{Aggregator, Entry, Quad, Visit},
 };
 
 use super::{
     jiggle::jiggle,
     particle::Particle,
     simulation::{Force, ForceBuilder},
+    spatial_index::SpatialIndex,
 };
 
 pub struct Collide {
@@ -59,6 +60,7 @@ impl ForceBuilder for Collide {
                 .collect(),
             strength: self.strength,
             iterations: self.iterations,
+            index: SpatialIndex::default(),
         })
     }
 }
@@ -68,26 +70,34 @@ pub struct CollideForce {
     radii: Vec<f64>,
     strength: f64,
     iterations: usize,
+    index: SpatialIndex<f64>,
+}
+
+/// Computes the largest radius within each quad.
+struct MaxRadius<'a>(&'a [f64]);
+
+impl Aggregator<f64, ParticleIndex> for MaxRadius<'_> {
+    fn leaf<'a>(&self, _: f64, _: f64, mut points: impl Iterator<Item = &'a ParticleIndex>) -> f64 {
+        // We only look at the data from the first leaf.
+        let first = points
+            .next()
+            .expect("leaves should contain at least one point");
+        self.0[usize::from(*first)]
+    }
+
+    fn combine(&self, children: [Option<&f64>; 4]) -> f64 {
+        let max_radius = children
+            .into_iter()
+            .flatten()
+            .max_by(|a, b| a.partial_cmp(b).expect("radii should be comparable"));
+        *max_radius.expect("the radius should be well-defined")
+    }
 }
 
 impl CollideForce {
-    pub fn force(&self, random: &mut Lcg, particles: &mut [Particle]) {
+    pub fn force(&mut self, random: &mut Lcg, particles: &mut [Particle]) {
         let iterations = self.iterations;
 
-        let prepare = |mut quad: Quad<'_, f64, ParticleIndex>| match quad.inner() {
-            Entry::Leaf { data, .. } => {
-                // We only look at the data from the first leaf.
-                *quad.value_mut() = self.radii[usize::from(*data)];
-            }
-            Entry::Internal { children } => {
-                let max_radius = children
-                    .iter()
-                    .filter_map(|c| c.map(|c| c))
-                    .max_by(|a, b| a.partial_cmp(b).expect("radii should be comparable"));
-                *quad.value_mut() = *max_radius.expect("the radius should be well-defined");
-            }
-        };
-
         let mut apply = |index: ParticleIndex,
                          xi: f64,
                          yi: f64,
@@ -142,18 +152,14 @@ impl CollideForce {
             Visit::Continue
         };
 
+        // The positions don't change between iterations, so the tree only needs to be updated once.
+        let (tree, positions) = self.index.update(particles.iter());
+        tree.aggregate(&MaxRadius(&self.radii));
+
         for _ in 0..iterations {
-            // TODO(grtlr): get rid of this!
-            let tmp = particles
-                .iter()
-                .map(|node| (node.x, node.y, node.index))
-                .collect::<Vec<_>>();
-            let mut tree = Quadtree::<f64, ParticleIndex>::from_particles(tmp.iter().copied());
-            tree.visit_after(prepare);
-
-            for (xi, yi, index) in tmp {
-                let ri = self.radii[usize::from(index)];
-                tree.visit(|quad| apply(index, xi, yi, ri, particles, quad));
+            for (i, &[xi, yi]) in positions.iter().enumerate() {
+                let ri = self.radii[i];
+                tree.visit(|quad| apply(i.into(), xi, yi, ri, particles, quad));
             }
         }
     }
@@ -0,0 +1,226 @@
+use crate::{
+    force::particle::ParticleIndex,
+    lcg::Lcg,
+    quadtree::{Aggregator, Entry, Quad, Visit},
+};
+
+use super::{
+    jiggle::jiggle,
+    link::get_pair_mut,
+    particle::Particle,
+    simulation::{Force, ForceBuilder},
+    spatial_index::SpatialIndex,
+};
+
+/// Prevents axis-aligned rectangles from overlapping.
+///
+/// Overlaps are resolved along the axis of least penetration.
+pub struct CollideRect {
+    strength: f64,
+    iterations: usize,
+    width_fn: Box<dyn Fn(usize) -> f64>,
+    height_fn: Box<dyn Fn(usize) -> f64>,
+}
+
+impl Default for CollideRect {
+    fn default() -> Self {
+        Self {
+            strength: 1.0,
+            iterations: 1,
+            width_fn: Box::new(|_| 1.0),
+            height_fn: Box::new(|_| 1.0),
+        }
+    }
+}
+
+impl CollideRect {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn width<F>(mut self, f: F) -> Self
+    where
+        F: Fn(usize) -> f64 + 'static,
+    {
+        self.width_fn = Box::new(f);
+        self
+    }
+
+    pub fn height<F>(mut self, f: F) -> Self
+    where
+        F: Fn(usize) -> f64 + 'static,
+    {
+        self.height_fn = Box::new(f);
+        self
+    }
+
+    pub fn iterations(mut self, iterations: usize) -> Self {
+        self.iterations = iterations;
+        self
+    }
+
+    pub fn strength(mut self, strength: f64) -> Self {
+        self.strength = strength;
+        self
+    }
+}
+
+impl ForceBuilder for CollideRect {
+    fn initialize(self, particles: &[Particle]) -> Force {
+        Force::CollideRect(CollideRectForce {
+            half_extents: particles
+                .iter()
+                .map(|n| {
+                    let i = n.index.into();
+                    [(self.width_fn)(i) / 2.0, (self.height_fn)(i) / 2.0]
+                })
+                .collect(),
+            strength: self.strength,
+            iterations: self.iterations,
+            index: SpatialIndex::default(),
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct CollideRectForce {
+    half_extents: Vec<[f64; 2]>,
+    strength: f64,
+    iterations: usize,
+    index: SpatialIndex<[f64; 2]>,
+}
+
+/// Computes the largest half-extents within each quad, per axis.
+struct MaxHalfExtents<'a>(&'a [[f64; 2]]);
+
+impl Aggregator<[f64; 2], ParticleIndex> for MaxHalfExtents<'_> {
+    fn leaf<'a>(
+        &self,
+        _: f64,
+        _: f64,
+        points: impl Iterator<Item = &'a ParticleIndex>,
+    ) -> [f64; 2] {
+        points
+            .map(|&i| self.0[usize::from(i)])
+            .fold([0.0, 0.0], max_half_extents)
+    }
+
+    fn combine(&self, children: [Option<&[f64; 2]>; 4]) -> [f64; 2] {
+        children
+            .into_iter()
+            .flatten()
+            .copied()
+            .fold([0.0, 0.0], max_half_extents)
+    }
+}
+
+impl CollideRectForce {
+    pub fn force(&mut self, random: &mut Lcg, particles: &mut [Particle]) {
+        let mut resolve =
+            |index: ParticleIndex, other: ParticleIndex, particles: &mut [Particle]| {
+                let [wi, hi] = self.half_extents[usize::from(index)];
+                let [wj, hj] = self.half_extents[usize::from(other)];
+                let Some((node, data)) = get_pair_mut(particles, index, other) else {
+                    return;
+                };
+
+                let mut x = node.x + node.vx - data.x - data.vx;
+                let mut y = node.y + node.vy - data.y - data.vy;
+                let px = wi + wj - x.abs();
+                let py = hi + hj - y.abs();
+                if px <= 0.0 || py <= 0.0 {
+                    return;
+                }
+
+                let (ai, aj) = (wi * hi, wj * hj);
+                let frac = if ai + aj > 0.0 { aj / (ai + aj) } else { 0.5 };
+
+                if px < py {
+                    if x == 0.0 {
+                        x = jiggle(random);
+                    }
+                    let l = x.signum() * px * self.strength;
+                    node.vx += l * frac;
+                    data.vx -= l * (1.0 - frac);
+                } else {
+                    if y == 0.0 {
+                        y = jiggle(random);
+                    }
+                    let l = y.signum() * py * self.strength;
+                    node.vy += l * frac;
+                    data.vy -= l * (1.0 - frac);
+                }
+            };
+
+        let mut apply = |index: ParticleIndex,
+                         xi: f64,
+                         yi: f64,
+                         [wi, hi]: [f64; 2],
+                         particles: &mut [Particle],
+                         quad: Quad<'_, [f64; 2], ParticleIndex>|
+         -> Visit {
+            match quad.inner() {
+                Entry::Leaf { data, others, .. } => {
+                    for &other in std::iter::once(data).chain(others.unwrap_or_default()) {
+                        // Every pair is visited twice, so we only resolve it from one side.
+                        if other > index {
+                            resolve(index, other, particles);
+                        }
+                    }
+                    Visit::Continue
+                }
+                Entry::Internal { .. } => {
+                    let [x0, y0, x1, y1] = quad.extent().into();
+                    let [wj, hj] = *quad.value();
+                    let (w, h) = (wi + wj, hi + hj);
+                    // We don't consider quads that are further away than the combined half-extents.
+                    Visit::stop_if(x0 > xi + w || x1 < xi - w || y0 > yi + h || y1 < yi - h)
+                }
+            }
+        };
+
+        for _ in 0..self.iterations {
+            let (tree, positions) = self.index.update(
+                particles
+                    .iter()
+                    .map(|node| [node.x + node.vx, node.y + node.vy]),
+            );
+            tree.aggregate(&MaxHalfExtents(&self.half_extents));
+
+            for (i, &[xi, yi]) in positions.iter().enumerate() {
+                let half_extent = self.half_extents[i];
+                tree.visit(|quad| apply(i.into(), xi, yi, half_extent, particles, quad));
+            }
+        }
+    }
+}
+
+fn max_half_extents(a: [f64; 2], b: [f64; 2]) -> [f64; 2] {
+    [f64::max(a[0], b[0]), f64::max(a[1], b[1])]
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Node, SimulationBuilder};
+
+    use super::*;
+
+    #[test]
+    fn separates_along_axis_of_least_penetration() {
+        let mut simulation = SimulationBuilder::default()
+            .build([
+                Node::default().position(0.0, 0.0),
+                Node::default().position(8.0, 1.0),
+            ])
+            .add_force(
+                "collide",
+                CollideRect::new().width(|_| 10.0).height(|_| 4.0),
+            );
+
+        let positions = simulation.iter().last().unwrap();
+
+        // The boxes overlap by 2 along x and 3 along y, so they are pushed apart horizontally.
+        assert!(positions[1][0] - positions[0][0] >= 10.0 - 1e-6);
+        approx::assert_abs_diff_eq!(positions[1][1] - positions[0][1], 1.0, epsilon = 1e-6);
+    }
+}
@@ -0,0 +1,225 @@
+use super::particle::Particle;
+
+/// The coordinate that a [`Constraint`] acts on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Axis {
+    X,
+    Y,
+}
+
+impl Axis {
+    fn position(self, particle: &Particle) -> f64 {
+        match self {
+            Self::X => particle.x,
+            Self::Y => particle.y,
+        }
+    }
+
+    fn position_mut(self, particle: &mut Particle) -> &mut f64 {
+        match self {
+            Self::X => &mut particle.x,
+            Self::Y => &mut particle.y,
+        }
+    }
+
+    fn is_fixed(self, particle: &Particle) -> bool {
+        match self {
+            Self::X => particle.fx.is_some(),
+            Self::Y => particle.fy.is_some(),
+        }
+    }
+}
+
+/// A hard constraint on the positions of particles, similar to the ones in [WebCoLa](https://ialab.it.monash.edu/webcola/).
+///
+/// Constraints are enforced by [`Simulation`](crate::Simulation) after each tick, by projecting
+/// the positions onto the constraint set. Fixed particles are never moved by a constraint.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Constraint {
+    /// Keeps `right` exactly `gap` units after `left`.
+    Equality {
+        axis: Axis,
+        left: usize,
+        right: usize,
+        gap: f64,
+    },
+    /// Keeps `right` at least `gap` units after `left`.
+    Inequality {
+        axis: Axis,
+        left: usize,
+        right: usize,
+        gap: f64,
+    },
+    /// Gives all `nodes` the same coordinate.
+    Alignment { axis: Axis, nodes: Vec<usize> },
+    /// Keeps the `nodes` in the given order, with at least `gap` units between consecutive nodes.
+    Order {
+        axis: Axis,
+        nodes: Vec<usize>,
+        gap: f64,
+    },
+}
+
+impl Constraint {
+    pub fn equality(axis: Axis, left: usize, right: usize, gap: f64) -> Self {
+        Self::Equality {
+            axis,
+            left,
+            right,
+            gap,
+        }
+    }
+
+    pub fn inequality(axis: Axis, left: usize, right: usize, gap: f64) -> Self {
+        Self::Inequality {
+            axis,
+            left,
+            right,
+            gap,
+        }
+    }
+
+    pub fn alignment(axis: Axis, nodes: impl IntoIterator<Item = usize>) -> Self {
+        Self::Alignment {
+            axis,
+            nodes: nodes.into_iter().collect(),
+        }
+    }
+
+    pub fn order(axis: Axis, nodes: impl IntoIterator<Item = usize>, gap: f64) -> Self {
+        Self::Order {
+            axis,
+            nodes: nodes.into_iter().collect(),
+            gap,
+        }
+    }
+
+    /// Moves the particles the least amount necessary to satisfy the constraint.
+    pub(crate) fn project(&self, particles: &mut [Particle]) {
+        match self {
+            Self::Equality {
+                axis,
+                left,
+                right,
+                gap,
+            } => separate(*axis, *left, *right, *gap, true, particles),
+            Self::Inequality {
+                axis,
+                left,
+                right,
+                gap,
+            } => separate(*axis, *left, *right, *gap, false, particles),
+            Self::Alignment { axis, nodes } => {
+                let fixed = nodes
+                    .iter()
+                    .filter(|&&i| axis.is_fixed(&particles[i]))
+                    .collect::<Vec<_>>();
+                // Fixed particles dictate the coordinate, otherwise we meet in the middle.
+                let anchors = if fixed.is_empty() {
+                    nodes.iter().collect()
+                } else {
+                    fixed
+                };
+                if anchors.is_empty() {
+                    return;
+                }
+                let target = anchors
+                    .iter()
+                    .map(|&&i| axis.position(&particles[i]))
+                    .sum::<f64>()
+                    / anchors.len() as f64;
+                for &i in nodes {
+                    if !axis.is_fixed(&particles[i]) {
+                        *axis.position_mut(&mut particles[i]) = target;
+                    }
+                }
+            }
+            Self::Order { axis, nodes, gap } => {
+                for pair in nodes.windows(2) {
+                    separate(*axis, pair[0], pair[1], *gap, false, particles);
+                }
+            }
+        }
+    }
+}
+
+fn separate(
+    axis: Axis,
+    left: usize,
+    right: usize,
+    gap: f64,
+    equal: bool,
+    particles: &mut [Particle],
+) {
+    if left == right {
+        return;
+    }
+
+    let violation = axis.position(&particles[left]) + gap - axis.position(&particles[right]);
+    if violation == 0.0 || (!equal && violation < 0.0) {
+        return;
+    }
+
+    let wl = if axis.is_fixed(&particles[left]) {
+        0.0
+    } else {
+        1.0
+    };
+    let wr = if axis.is_fixed(&particles[right]) {
+        0.0
+    } else {
+        1.0
+    };
+    if wl + wr == 0.0 {
+        return;
+    }
+
+    *axis.position_mut(&mut particles[left]) -= violation * wl / (wl + wr);
+    *axis.position_mut(&mut particles[right]) += violation * wr / (wl + wr);
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{ManyBody, Node, PositionX, PositionY, SimulationBuilder};
+
+    use super::*;
+
+    #[test]
+    fn enforces_separation_and_alignment() {
+        let mut simulation = SimulationBuilder::default()
+            .build([
+                Node::default().position(0.0, 0.0),
+                Node::default().position(1.0, 5.0),
+                Node::default().position(-3.0, -8.0),
+            ])
+            .add_force("x", PositionX::default())
+            .add_force("y", PositionY::default())
+            .add_constraint(Constraint::inequality(Axis::X, 0, 1, 40.0))
+            .add_constraint(Constraint::alignment(Axis::Y, [0, 1]));
+
+        for positions in simulation.iter() {
+            assert!(positions[1][0] - positions[0][0] >= 40.0 - 1e-6);
+            approx::assert_abs_diff_eq!(positions[0][1], positions[1][1], epsilon = 1e-6);
+        }
+    }
+
+    #[test]
+    fn keeps_order_and_respects_fixed_nodes() {
+        let mut simulation = SimulationBuilder::default()
+            .build([
+                Node::default().fixed_position(0.0, 0.0),
+                Node::default().position(0.0, -10.0),
+                Node::default().position(0.0, -20.0),
+            ])
+            .add_force("charge", ManyBody::new())
+            .add_constraint(Constraint::order(Axis::Y, [0, 1, 2], 10.0))
+            .add_constraint(Constraint::equality(Axis::X, 0, 2, 5.0));
+
+        let positions = simulation.iter().last().unwrap();
+
+        assert_eq!(positions[0], [0.0, 0.0]);
+        assert!(positions[1][1] >= 10.0 - 1e-6);
+        assert!(positions[2][1] - positions[1][1] >= 10.0 - 1e-6);
+        approx::assert_abs_diff_eq!(positions[2][0], 5.0, epsilon = 1e-6);
+    }
+}
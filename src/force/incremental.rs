@@ -0,0 +1,113 @@
+use std::collections::VecDeque;
+
+use crate::{graph::Graph, lcg::Lcg};
+
+use super::{node::Node, particle::Particle, placement::initial_position};
+
+/// Describes how [`Simulation::extend`](crate::Simulation::extend) adds new nodes to a
+/// simulation that has already been laid out.
+///
+/// Each new node is placed at the centroid of its neighbors that already have a position, plus
+/// some jitter. Afterwards, only the new nodes and their surroundings are reheated, so that the
+/// rest of the layout stays where it is.
+#[derive(Clone, Debug)]
+pub struct Incremental {
+    links: Vec<(usize, usize)>,
+    alpha: f64,
+    hops: usize,
+    jitter: f64,
+}
+
+impl Incremental {
+    /// Creates the placement from the links of the extended graph, which decide where new nodes
+    /// go and which existing nodes are reheated.
+    ///
+    /// The links can refer to existing and new nodes alike. Extending a simulation panics if a link
+    /// refers to a node that does not exist.
+    pub fn new(links: impl IntoIterator<Item = (usize, usize)>) -> Self {
+        Self {
+            links: links.into_iter().collect(),
+            alpha: 0.3,
+            hops: 1,
+            jitter: 1.0,
+        }
+    }
+
+    /// Sets the alpha value that the simulation is reheated to, if it is currently lower.
+    pub fn alpha(mut self, alpha: f64) -> Self {
+        self.alpha = alpha;
+        self
+    }
+
+    /// Sets how many hops away from a new node existing nodes are still allowed to move.
+    ///
+    /// The mobility of those nodes decreases linearly with their distance to the new nodes.
+    pub fn hops(mut self, hops: usize) -> Self {
+        self.hops = hops;
+        self
+    }
+
+    /// Sets the maximum offset of a new node from the centroid of its neighbors.
+    pub fn jitter(mut self, jitter: f64) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    pub(super) fn reheat_alpha(&self) -> f64 {
+        self.alpha
+    }
+
+    /// Appends the `nodes` to the `particles` and returns the mobility of every particle.
+    pub(super) fn extend(
+        self,
+        particles: &mut Vec<Particle>,
+        nodes: Vec<Node>,
+        random: &mut Lcg,
+    ) -> Vec<f64> {
+        let start = particles.len();
+        let len = start + nodes.len();
+
+        let graph = Graph::from_links(len, self.links.iter().copied());
+
+        for (offset, node) in nodes.into_iter().enumerate() {
+            let ix = start + offset;
+            let centroid =
+                graph.neighbor_centroid(ix, |j| (j < ix).then(|| [particles[j].x, particles[j].y]));
+
+            let mut jitter = || (random.next().unwrap_or_default() * 2.0 - 1.0) * self.jitter;
+            let particle = node.build_with_pos(ix.into(), || match centroid {
+                None => initial_position(ix),
+                Some([cx, cy]) => [cx + jitter(), cy + jitter()],
+            });
+            particles.push(particle);
+        }
+
+        // Breadth-first search from all new nodes at once.
+        let mut hops = vec![usize::MAX; len];
+        let mut queue = (start..len).collect::<VecDeque<_>>();
+        for &ix in &queue {
+            hops[ix] = 0;
+        }
+        while let Some(ix) = queue.pop_front() {
+            if hops[ix] >= self.hops {
+                continue;
+            }
+            for &j in graph.neighbors(ix) {
+                if hops[j] == usize::MAX {
+                    hops[j] = hops[ix] + 1;
+                    queue.push_back(j);
+                }
+            }
+        }
+
+        hops.into_iter()
+            .map(|h| {
+                if h == usize::MAX {
+                    0.0
+                } else {
+                    1.0 - h as f64 / (self.hops + 1) as f64
+                }
+            })
+            .collect()
+    }
+}
@@ -122,7 +122,7 @@ pub struct LinkForce {
     iterations: usize,
 }
 
-fn get_pair_mut(
+pub(super) fn get_pair_mut(
     slice: &mut [Particle],
     i: ParticleIndex,
     j: ParticleIndex,
@@ -161,6 +161,8 @@ impl LinkForce {
                 if y == 0.0 {
                     y = jiggle(random);
                 }
+                // `allow`: We deliberately mirror the computation in `d3-force`.
+                #[allow(clippy::imprecise_flops)]
                 let l = (x * x + y * y).sqrt();
                 let l = (l - self.distances[i]) / l * alpha * self.strengths[i];
 
@@ -1,15 +1,16 @@
 use crate::{
     lcg::Lcg,
-    quadtree::{Entry, Quad, Quadtree, Visit},
+    quadtree::{Aggregator, Entry, Quad, Visit},
 };
 
 use super::{
     jiggle::jiggle,
     particle::{Particle, ParticleIndex},
     simulation::{Force, ForceBuilder},
+    spatial_index::SpatialIndex,
 };
 
-pub struct NodeFn(Box<dyn Fn(ParticleIndex, usize) -> f64>);
+pub struct NodeFn(pub(super) Box<dyn Fn(ParticleIndex, usize) -> f64>);
 
 impl From<f64> for NodeFn {
     fn from(value: f64) -> Self {
@@ -68,6 +69,7 @@ impl ForceBuilder for ManyBody {
             distance_min_2: self.distance_min * self.distance_min,
             distance_max_2: self.distance_max * self.distance_max,
             theta_2: self.theta * self.theta,
+            index: SpatialIndex::default(),
         })
     }
 }
@@ -77,6 +79,7 @@ pub struct ManyBodyForce {
     distance_min_2: f64,
     distance_max_2: f64,
     theta_2: f64,
+    index: SpatialIndex<Charge>,
 }
 
 #[derive(Default)]
@@ -86,38 +89,34 @@ struct Charge {
     strength: f64,
 }
 
-impl ManyBodyForce {
-    pub fn force(&self, alpha: f64, random: &mut Lcg, particles: &mut [Particle]) {
-        let accumulate = |mut quad: Quad<'_, Charge, ParticleIndex>| match quad.inner() {
-            Entry::Leaf { data, others, x, y } => {
-                let strength = self.strengths[usize::from(*data)]
-                    + others
-                        .unwrap_or_default()
-                        .iter()
-                        .map(|&&d| self.strengths[usize::from(d)])
-                        .sum::<f64>();
-
-                *quad.value_mut() = Charge { x, y, strength };
-            }
-            Entry::Internal { children } => {
-                let mut result = Charge::default();
-                let mut weight = 0.0;
-
-                for &c in &children {
-                    if let Some(q) = c {
-                        let c = q.strength.abs();
-                        result.strength += q.strength;
-                        weight += c;
-                        result.x += c * q.x;
-                        result.y += c * q.y;
-                    }
-                }
-                result.x /= weight;
-                result.y /= weight;
-                *quad.value_mut() = result;
-            }
-        };
+/// Sums up the strengths of the particles at their center of charge.
+struct Charges<'a>(&'a [f64]);
+
+impl Aggregator<Charge, ParticleIndex> for Charges<'_> {
+    fn leaf<'a>(&self, x: f64, y: f64, points: impl Iterator<Item = &'a ParticleIndex>) -> Charge {
+        let strength = points.map(|&i| self.0[usize::from(i)]).sum();
+        Charge { x, y, strength }
+    }
 
+    fn combine(&self, children: [Option<&Charge>; 4]) -> Charge {
+        let mut result = Charge::default();
+        let mut weight = 0.0;
+
+        for q in children.into_iter().flatten() {
+            let c = q.strength.abs();
+            result.strength += q.strength;
+            weight += c;
+            result.x += c * q.x;
+            result.y += c * q.y;
+        }
+        result.x /= weight;
+        result.y /= weight;
+        result
+    }
+}
+
+impl ManyBodyForce {
+    pub fn force(&mut self, alpha: f64, random: &mut Lcg, particles: &mut [Particle]) {
         let mut apply = |index: ParticleIndex,
                          particles: &mut [Particle],
                          quad: Quad<'_, Charge, ParticleIndex>|
@@ -184,18 +183,11 @@ impl ManyBodyForce {
             Visit::Continue
         };
 
-        let mut tree = Quadtree::<Charge, ParticleIndex>::from_particles(
-            particles.iter().map(|n| (n.x, n.y, n.index)),
-        );
-        tree.visit_after(accumulate);
-
-        let tmp = particles
-            .iter()
-            .map(|node| (node.index))
-            .collect::<Vec<_>>();
+        let (tree, _) = self.index.update(particles.iter());
+        tree.aggregate(&Charges(&self.strengths));
 
-        for index in tmp {
-            tree.visit(|quad| apply(index, particles, quad));
+        for i in 0..particles.len() {
+            tree.visit(|quad| apply(i.into(), particles, quad));
         }
     }
 }
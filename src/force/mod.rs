@@ -1,18 +1,37 @@
+mod anchor;
+mod angular;
 mod center;
 mod collide;
-mod jiggle;
+mod collide_rect;
+mod constraint;
+mod incremental;
+pub(crate) mod jiggle;
 mod link;
 mod many_body;
 mod node;
-mod particle;
+mod obstacle;
+pub(crate) mod particle;
+mod placement;
 mod position;
 mod simulation;
+mod spatial_index;
+mod wells;
 
 pub use node::Node;
-pub use simulation::{ForceBuilder, Simulation, SimulationBuilder};
+pub(crate) use placement::initial_position;
+pub(crate) use simulation::Force;
+pub use simulation::{ForceBuilder, Simulation, SimulationBuilder, SimulationExtension};
 
+pub use anchor::Anchor;
+pub use angular::AngularResolution;
 pub use center::Center;
 pub use collide::Collide;
+pub use collide_rect::CollideRect;
+pub use constraint::{Axis, Constraint};
+pub use incremental::Incremental;
 pub use link::Link;
 pub use many_body::ManyBody;
+pub use obstacle::{Obstacle, Obstacles};
+pub use placement::{InitialLayout, Placement};
 pub use position::{PositionX, PositionY};
+pub use wells::{Falloff, Well, Wells};
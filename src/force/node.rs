@@ -24,8 +24,13 @@ impl Node {
         self
     }
 
+    /// Returns the position, if it was set explicitly.
+    pub(crate) fn explicit_position(&self) -> Option<[f64; 2]> {
+        self.position
+    }
+
     /// Builds a particle with a given position if it is not already set.
-    pub(super) fn build_with_pos(
+    pub(crate) fn build_with_pos(
         self,
         index: ParticleIndex,
         pos_fn: impl FnMut() -> [f64; 2],
@@ -0,0 +1,254 @@
+use crate::lcg::Lcg;
+
+use super::{
+    jiggle::jiggle,
+    particle::Particle,
+    simulation::{Force, ForceBuilder},
+};
+
+/// A static region that particles are not allowed to enter.
+///
+/// Obstacles are not particles: they never move and are not affected by other forces.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Obstacle {
+    Circle {
+        x: f64,
+        y: f64,
+        radius: f64,
+    },
+    /// A simple polygon, given by its vertices. The polygon is closed implicitly.
+    Polygon(Vec<[f64; 2]>),
+}
+
+impl Obstacle {
+    pub fn circle(x: f64, y: f64, radius: f64) -> Self {
+        Self::Circle { x, y, radius }
+    }
+
+    pub fn polygon(vertices: impl IntoIterator<Item = [f64; 2]>) -> Self {
+        Self::Polygon(vertices.into_iter().collect())
+    }
+
+    /// Returns the displacement that moves a circle at `(x, y)` with radius `r` out of the obstacle.
+    fn displacement(&self, x: f64, y: f64, r: f64, random: &mut Lcg) -> Option<[f64; 2]> {
+        match self {
+            Self::Circle {
+                x: cx,
+                y: cy,
+                radius,
+            } => {
+                let mut dx = x - cx;
+                let mut dy = y - cy;
+                let min = radius + r;
+                let mut l = dx * dx + dy * dy;
+                if l >= min * min {
+                    return None;
+                }
+                if dx == 0.0 {
+                    dx = jiggle(random);
+                    l += dx * dx;
+                }
+                if dy == 0.0 {
+                    dy = jiggle(random);
+                    l += dy * dy;
+                }
+                let l = l.sqrt();
+                let push = (min - l) / l;
+                Some([dx * push, dy * push])
+            }
+            Self::Polygon(vertices) => {
+                let ([qx, qy], inside) = closest_on_boundary(vertices, x, y)?;
+                let dx = qx - x;
+                let dy = qy - y;
+                let l = dx.hypot(dy);
+                if inside {
+                    if l == 0.0 {
+                        // We are exactly on the boundary, so we can't tell which way is out.
+                        return Some([jiggle(random), jiggle(random)]);
+                    }
+                    let push = (l + r) / l;
+                    Some([dx * push, dy * push])
+                } else if l < r && l > 0.0 {
+                    let push = (l - r) / l;
+                    Some([dx * push, dy * push])
+                } else {
+                    None
+                }
+            }
+        }
+    }
+}
+
+/// Returns the point on the boundary of the polygon that is closest to `(x, y)`, and whether
+/// `(x, y)` lies inside of the polygon.
+fn closest_on_boundary(vertices: &[[f64; 2]], x: f64, y: f64) -> Option<([f64; 2], bool)> {
+    let mut closest = None;
+    let mut best = f64::INFINITY;
+    let mut inside = false;
+
+    let edges = vertices.iter().zip(vertices.iter().cycle().skip(1));
+    for (&[ax, ay], &[bx, by]) in edges {
+        // Even-odd rule for the containment test.
+        if (ay > y) != (by > y) && x < (bx - ax) * (y - ay) / (by - ay) + ax {
+            inside = !inside;
+        }
+
+        let (ex, ey) = (bx - ax, by - ay);
+        let len_2 = ex * ex + ey * ey;
+        let t = if len_2 > 0.0 {
+            (((x - ax) * ex + (y - ay) * ey) / len_2).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        let (px, py) = (ax + t * ex, ay + t * ey);
+        let d = (px - x) * (px - x) + (py - y) * (py - y);
+        if d < best {
+            best = d;
+            closest = Some([px, py]);
+        }
+    }
+
+    closest.map(|c| (c, inside))
+}
+
+/// Pushes particles out of a set of static [`Obstacle`]s.
+pub struct Obstacles {
+    obstacles: Vec<Obstacle>,
+    strength: f64,
+    radius_fn: Box<dyn Fn(usize) -> f64>,
+}
+
+impl Default for Obstacles {
+    fn default() -> Self {
+        Self {
+            obstacles: Vec::new(),
+            strength: 1.0,
+            radius_fn: Box::new(|_| 0.0),
+        }
+    }
+}
+
+impl Obstacles {
+    pub fn new(obstacles: impl IntoIterator<Item = Obstacle>) -> Self {
+        Self {
+            obstacles: obstacles.into_iter().collect(),
+            ..Default::default()
+        }
+    }
+
+    pub fn circle(mut self, x: f64, y: f64, radius: f64) -> Self {
+        self.obstacles.push(Obstacle::circle(x, y, radius));
+        self
+    }
+
+    pub fn polygon(mut self, vertices: impl IntoIterator<Item = [f64; 2]>) -> Self {
+        self.obstacles.push(Obstacle::polygon(vertices));
+        self
+    }
+
+    /// Sets the radius of the particles, which is kept clear of the obstacles.
+    pub fn radius<F>(mut self, f: F) -> Self
+    where
+        F: Fn(usize) -> f64 + 'static,
+    {
+        self.radius_fn = Box::new(f);
+        self
+    }
+
+    pub fn strength(mut self, strength: f64) -> Self {
+        self.strength = strength;
+        self
+    }
+}
+
+impl ForceBuilder for Obstacles {
+    fn initialize(self, particles: &[Particle]) -> Force {
+        Force::Obstacles(ObstaclesForce {
+            radii: particles
+                .iter()
+                .map(|n| (self.radius_fn)(n.index.into()))
+                .collect(),
+            obstacles: self.obstacles,
+            strength: self.strength,
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ObstaclesForce {
+    obstacles: Vec<Obstacle>,
+    radii: Vec<f64>,
+    strength: f64,
+}
+
+impl ObstaclesForce {
+    pub fn force(&self, random: &mut Lcg, particles: &mut [Particle]) {
+        for node in particles.iter_mut() {
+            let r = self.radii[usize::from(node.index)];
+            for obstacle in &self.obstacles {
+                // We look at where the particle is headed to, similar to `Collide`.
+                let x = node.x + node.vx;
+                let y = node.y + node.vy;
+                if let Some([dx, dy]) = obstacle.displacement(x, y, r, random) {
+                    node.vx += dx * self.strength;
+                    node.vy += dy * self.strength;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Node, PositionX, PositionY, SimulationBuilder};
+
+    use super::*;
+
+    #[test]
+    fn pushes_particles_out_of_circles() {
+        let mut simulation = SimulationBuilder::default()
+            .build([Node::default().position(1.0, 0.0)])
+            .add_force("obstacles", Obstacles::default().circle(0.0, 0.0, 10.0));
+
+        let positions = simulation.iter().last().unwrap();
+
+        assert!(positions[0][0] >= 10.0 - 1e-6);
+        approx::assert_abs_diff_eq!(positions[0][1], 0.0, epsilon = 1e-3);
+    }
+
+    #[test]
+    fn keeps_particles_out_of_polygons() {
+        let square = [[-10.0, -10.0], [10.0, -10.0], [10.0, 10.0], [-10.0, 10.0]];
+        let mut simulation = SimulationBuilder::default()
+            .build([Node::default().position(8.0, 1.0)])
+            // Pulls the particle towards the center of the square. Forces are applied in the
+            // order of their names, so the obstacles are resolved last.
+            .add_force("center_x", PositionX::default())
+            .add_force("center_y", PositionY::default())
+            .add_force(
+                "obstacles",
+                Obstacles::new([Obstacle::polygon(square)]).radius(|_| 2.0),
+            );
+
+        for positions in simulation.iter() {
+            let [x, y] = positions[0];
+            assert!(
+                x >= 12.0 - 1e-6 || x <= -12.0 + 1e-6 || y >= 12.0 - 1e-6 || y <= -12.0 + 1e-6,
+                "particle entered the obstacle at ({x}, {y})"
+            );
+        }
+    }
+
+    #[test]
+    fn detects_containment_in_polygons() {
+        let triangle = [[0.0, 0.0], [4.0, 0.0], [0.0, 4.0]];
+        assert_eq!(
+            closest_on_boundary(&triangle, 1.0, 0.5),
+            Some(([1.0, 0.0], true))
+        );
+        assert_eq!(
+            closest_on_boundary(&triangle, 1.0, -2.0),
+            Some(([1.0, 0.0], false))
+        );
+    }
+}
@@ -1,5 +1,7 @@
 use std::hash::Hash;
 
+use crate::quadtree::Point;
+
 /// Reflects the index in the input list of particles.
 #[derive(Debug, Clone, Copy, PartialOrd, Ord, PartialEq, Eq, Hash)]
 #[repr(transparent)]
@@ -30,6 +32,16 @@ pub struct Particle {
     pub fy: Option<f64>,
 }
 
+impl Point for Particle {
+    fn x(&self) -> f64 {
+        self.x
+    }
+
+    fn y(&self) -> f64 {
+        self.y
+    }
+}
+
 impl Particle {
     pub fn new(index: impl Into<ParticleIndex>, x: f64, y: f64) -> Self {
         Self {
@@ -59,20 +71,31 @@ impl Particle {
             self.x = fx;
             self.vx = 0.0;
         } else {
-            // TODO: This is a somewhat hacky way to prevent particles from
-            // moving too far and therefore overflowing the quadtree.
-            self.x += self.vx.clamp(i32::MIN as f64, i32::MAX as f64);
-            self.vx *= velocity_decay;
+            advance(&mut self.x, &mut self.vx, velocity_decay);
         }
 
         if let Some(fy) = self.fy {
             self.y = fy;
             self.vy = 0.0;
         } else {
-            // TODO: This is a somewhat hacky way to prevent particles from
-            // moving too far and therefore overflowing the quadtree.
-            self.y += self.vy.clamp(i32::MIN as f64, i32::MAX as f64);
-            self.vy *= velocity_decay;
+            advance(&mut self.y, &mut self.vy, velocity_decay);
         }
     }
 }
+
+/// Bounds the coordinates of particles, so that squared distances between them stay finite.
+const MAX_COORDINATE: f64 = 1e150;
+
+/// Moves a coordinate by its velocity, keeping it finite.
+///
+/// Diverging forces can produce infinite or even NaN velocities. The former are clamped and the
+/// latter are dropped, so that the particle stays in every quadtree-based force.
+fn advance(position: &mut f64, velocity: &mut f64, velocity_decay: f64) {
+    let next = (*position + *velocity).clamp(-MAX_COORDINATE, MAX_COORDINATE);
+    if next.is_nan() {
+        *velocity = 0.0;
+    } else {
+        *position = next;
+        *velocity *= velocity_decay;
+    }
+}
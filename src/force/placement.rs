@@ -0,0 +1,229 @@
+use std::{f64::consts::TAU, fmt};
+
+use crate::{graph::Graph, lcg::Lcg};
+
+use super::node::Node;
+
+const INITIAL_RADIUS: f64 = 10.0;
+
+/// Computes initial positions for all nodes of a graph at once, see [`Placement::Layout`].
+pub trait InitialLayout {
+    /// Returns the positions of `len` nodes, in the order of their index.
+    fn positions(&self, len: usize) -> Vec<[f64; 2]>;
+}
+
+/// Decides where [`SimulationBuilder::build`](crate::SimulationBuilder::build) puts nodes that
+/// don't have an explicit position.
+///
+/// All strategies space the nodes roughly 10 units apart, like d3 does.
+#[derive(Default)]
+pub enum Placement {
+    /// Arranges the nodes in a phyllotaxis spiral, which is what d3 does.
+    #[default]
+    Phyllotaxis,
+    /// Distributes the nodes uniformly in a square that is centered at the origin.
+    Random { seed: u64 },
+    /// Distributes the nodes evenly on a circle that is centered at the origin.
+    Circle,
+    /// Arranges the nodes in a square grid, row by row.
+    Grid,
+    /// Places each node close to the centroid of its neighbors that already have a position.
+    ///
+    /// Nodes are placed in order of their index. Nodes without such neighbors fall back to the
+    /// phyllotaxis arrangement. Building panics if a link refers to a node that does not exist.
+    Neighbors(Vec<(usize, usize)>),
+    /// Places the nodes with a layout of the whole graph, such as
+    /// [`Spectral`](crate::layout::Spectral).
+    Layout(Box<dyn InitialLayout>),
+    /// Calls a closure with the index of each node.
+    Custom(Box<dyn Fn(usize) -> [f64; 2]>),
+}
+
+impl Placement {
+    pub fn random(seed: u64) -> Self {
+        Self::Random { seed }
+    }
+
+    pub fn neighbors(links: impl IntoIterator<Item = (usize, usize)>) -> Self {
+        Self::Neighbors(links.into_iter().collect())
+    }
+
+    pub fn layout(layout: impl InitialLayout + 'static) -> Self {
+        Self::Layout(Box::new(layout))
+    }
+
+    pub fn custom<F>(f: F) -> Self
+    where
+        F: Fn(usize) -> [f64; 2] + 'static,
+    {
+        Self::Custom(Box::new(f))
+    }
+
+    /// Computes the position of every node, keeping the ones that were set explicitly.
+    pub(crate) fn positions(&self, nodes: &[Node]) -> Vec<[f64; 2]> {
+        let n = nodes.len();
+        let explicit = |ix: usize, f: &mut dyn FnMut(usize) -> [f64; 2]| {
+            nodes[ix].explicit_position().unwrap_or_else(|| f(ix))
+        };
+
+        match self {
+            Self::Phyllotaxis => (0..n)
+                .map(|ix| explicit(ix, &mut initial_position))
+                .collect(),
+            Self::Random { seed } => {
+                let mut random = Lcg::new(*seed);
+                let half = INITIAL_RADIUS * (n as f64).sqrt();
+                let mut next = || (random.next().unwrap_or_default() * 2.0 - 1.0) * half;
+                (0..n)
+                    .map(|ix| explicit(ix, &mut |_| [next(), next()]))
+                    .collect()
+            }
+            Self::Circle => {
+                let radius = f64::max(INITIAL_RADIUS * n as f64 / TAU, INITIAL_RADIUS);
+                let mut on_circle = |ix: usize| {
+                    let angle = TAU * ix as f64 / n as f64;
+                    [radius * angle.cos(), radius * angle.sin()]
+                };
+                (0..n).map(|ix| explicit(ix, &mut on_circle)).collect()
+            }
+            Self::Grid => {
+                let columns = (n as f64).sqrt().ceil().max(1.0) as usize;
+                let rows = n.div_ceil(columns);
+                let offset_x = (columns - 1) as f64 / 2.0;
+                let offset_y = rows.saturating_sub(1) as f64 / 2.0;
+                let mut on_grid = |ix: usize| {
+                    let (row, column) = (ix / columns, ix % columns);
+                    [
+                        (column as f64 - offset_x) * INITIAL_RADIUS,
+                        (row as f64 - offset_y) * INITIAL_RADIUS,
+                    ]
+                };
+                (0..n).map(|ix| explicit(ix, &mut on_grid)).collect()
+            }
+            Self::Neighbors(links) => {
+                let graph = Graph::from_links(n, links.iter().copied());
+
+                let mut positions = nodes
+                    .iter()
+                    .map(Node::explicit_position)
+                    .collect::<Vec<_>>();
+                for ix in 0..n {
+                    if positions[ix].is_some() {
+                        continue;
+                    }
+                    let centroid = graph.neighbor_centroid(ix, |j| positions[j]);
+                    positions[ix] = Some(match centroid {
+                        None => initial_position(ix),
+                        Some([cx, cy]) => {
+                            // Offsets siblings in different directions, so they don't coincide.
+                            let angle = ix as f64 * golden_angle();
+                            [
+                                cx + INITIAL_RADIUS * angle.cos(),
+                                cy + INITIAL_RADIUS * angle.sin(),
+                            ]
+                        }
+                    });
+                }
+                positions.into_iter().flatten().collect()
+            }
+            Self::Layout(layout) => {
+                let positions = layout.positions(n);
+                (0..n)
+                    .map(|ix| explicit(ix, &mut |ix| positions[ix]))
+                    .collect()
+            }
+            Self::Custom(f) => (0..n).map(|ix| explicit(ix, &mut |ix| f(ix))).collect(),
+        }
+    }
+}
+
+impl fmt::Debug for Placement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Phyllotaxis => write!(f, "Phyllotaxis"),
+            Self::Random { seed } => f.debug_struct("Random").field("seed", seed).finish(),
+            Self::Circle => write!(f, "Circle"),
+            Self::Grid => write!(f, "Grid"),
+            Self::Neighbors(links) => f.debug_tuple("Neighbors").field(links).finish(),
+            Self::Layout(_) => write!(f, "Layout(..)"),
+            Self::Custom(_) => write!(f, "Custom(..)"),
+        }
+    }
+}
+
+fn golden_angle() -> f64 {
+    std::f64::consts::PI * (3.0 - (5.0f64).sqrt())
+}
+
+/// Creates the initial position of particles.
+pub(crate) fn initial_position(index: usize) -> [f64; 2] {
+    let radius = INITIAL_RADIUS * (0.5 + index as f64).sqrt();
+    let angle = index as f64 * golden_angle();
+    [radius * angle.cos(), radius * angle.sin()]
+}
+
+#[cfg(test)]
+mod test {
+    use crate::SimulationBuilder;
+
+    use super::*;
+
+    #[test]
+    fn keeps_explicit_positions() {
+        let nodes = [Node::default(), Node::default().position(42.0, 42.0)];
+        for placement in [
+            Placement::Phyllotaxis,
+            Placement::random(7),
+            Placement::Circle,
+            Placement::Grid,
+            Placement::neighbors([(0, 1)]),
+            Placement::custom(|_| [1.0, 2.0]),
+        ] {
+            let positions = placement.positions(&nodes);
+            assert_eq!(positions[1], [42.0, 42.0], "{placement:?}");
+        }
+    }
+
+    #[test]
+    fn arranges_nodes_in_a_grid() {
+        let positions = Placement::Grid.positions(&vec![Node::default(); 4]);
+        assert_eq!(
+            positions,
+            [[-5.0, -5.0], [5.0, -5.0], [-5.0, 5.0], [5.0, 5.0]]
+        );
+    }
+
+    #[test]
+    fn places_nodes_close_to_their_neighbors() {
+        let nodes = [
+            Node::default().position(100.0, 0.0),
+            Node::default().position(100.0, 20.0),
+            Node::default(),
+            Node::default(),
+        ];
+        let positions = Placement::neighbors([(0, 2), (1, 2), (2, 3)]).positions(&nodes);
+
+        let [x, y] = positions[2];
+        approx::assert_abs_diff_eq!((x - 100.0).hypot(y - 10.0), INITIAL_RADIUS, epsilon = 1e-9);
+        let [cx, cy] = positions[3];
+        approx::assert_abs_diff_eq!((cx - x).hypot(cy - y), INITIAL_RADIUS, epsilon = 1e-9);
+    }
+
+    #[test]
+    #[should_panic(expected = "refers to a node that does not exist")]
+    fn rejects_links_to_missing_nodes() {
+        Placement::neighbors([(0, 2)]).positions(&[Node::default(), Node::default()]);
+    }
+
+    #[test]
+    fn uses_placement_in_builder() {
+        let simulation = SimulationBuilder::default()
+            .with_placement(Placement::custom(|i| [i as f64, 0.0]))
+            .build([Node::default(), Node::default()]);
+
+        assert_eq!(
+            simulation.positions().collect::<Vec<_>>(),
+            [[0.0, 0.0], [1.0, 0.0]]
+        );
+    }
+}
@@ -1,10 +1,18 @@
 use std::collections::BTreeMap;
 
-use crate::lcg::Lcg;
+use crate::{bundling::BundleForce, lcg::Lcg};
 
+use super::anchor::AnchorForce;
+use super::angular::AngularResolutionForce;
 use super::center::CenterForce;
+use super::collide_rect::CollideRectForce;
+use super::constraint::Constraint;
+use super::incremental::Incremental;
 use super::node::Node;
+use super::obstacle::ObstaclesForce;
+use super::placement::Placement;
 use super::position::{PositionXForce, PositionYForce};
+use super::wells::WellsForce;
 use super::{collide::CollideForce, link::LinkForce, many_body::ManyBodyForce, particle::Particle};
 
 pub trait ForceBuilder {
@@ -12,12 +20,18 @@ pub trait ForceBuilder {
 }
 
 pub enum Force {
+    Anchor(AnchorForce),
+    AngularResolution(AngularResolutionForce),
+    Bundle(BundleForce),
     Collide(CollideForce),
+    CollideRect(CollideRectForce),
     Center(CenterForce),
     PositionX(PositionXForce),
     PositionY(PositionYForce),
     Link(LinkForce),
     ManyBody(ManyBodyForce),
+    Obstacles(ObstaclesForce),
+    Wells(WellsForce),
 }
 
 #[derive(Debug)]
@@ -28,6 +42,8 @@ pub struct SimulationBuilder {
     alpha_target: f64,
     velocity_decay: f64,
     random: Lcg,
+    placement: Placement,
+    constraint_iterations: usize,
 }
 
 impl Default for SimulationBuilder {
@@ -40,6 +56,8 @@ impl Default for SimulationBuilder {
             alpha_target: 0.0,
             velocity_decay: 0.6,
             random: Lcg::default(),
+            placement: Placement::default(),
+            constraint_iterations: 10,
         }
     }
 }
@@ -78,16 +96,20 @@ impl SimulationBuilder {
         self.random = random;
         self
     }
-}
 
-/// Creates the initial position of particles.
-fn initial_position(index: usize) -> [f64; 2] {
-    let initial_radius = 10.0;
-    let initial_angle = std::f64::consts::PI * (3.0 - (5.0f64).sqrt());
+    /// Sets how often the constraints are projected after each tick, see [`Constraint`].
+    ///
+    /// More iterations are necessary if constraints share particles.
+    pub fn with_constraint_iterations(mut self, iterations: usize) -> Self {
+        self.constraint_iterations = iterations;
+        self
+    }
 
-    let radius = initial_radius * (0.5 + index as f64).sqrt();
-    let angle = index as f64 * initial_angle;
-    [radius * angle.cos(), radius * angle.sin()]
+    /// Sets how nodes without an explicit position are placed, see [`Placement`].
+    pub fn with_placement(mut self, placement: Placement) -> Self {
+        self.placement = placement;
+        self
+    }
 }
 
 impl SimulationBuilder {
@@ -95,10 +117,13 @@ impl SimulationBuilder {
     where
         N: Into<Node>,
     {
-        let particles = particles
+        let nodes = particles.into_iter().map(Into::into).collect::<Vec<Node>>();
+        let positions = self.placement.positions(&nodes);
+
+        let particles = nodes
             .into_iter()
             .enumerate()
-            .map(|(ix, p)| p.into().build_with_pos(ix.into(), || initial_position(ix)))
+            .map(|(ix, p)| p.build_with_pos(ix.into(), || positions[ix]))
             .collect();
 
         Simulation {
@@ -110,6 +135,9 @@ impl SimulationBuilder {
             particles,
             random: self.random.clone(),
             forces: Default::default(),
+            constraints: Vec::new(),
+            constraint_iterations: self.constraint_iterations,
+            mobility: None,
         }
     }
 }
@@ -123,6 +151,10 @@ pub struct Simulation {
     random: Lcg,
     forces: BTreeMap<String, Force>,
     particles: Vec<Particle>,
+    constraints: Vec<Constraint>,
+    constraint_iterations: usize,
+    /// Scales the velocities of the particles after an incremental update.
+    mobility: Option<Vec<f64>>,
 }
 
 pub struct SimulationIter<'a> {
@@ -141,7 +173,7 @@ impl<'a> Iterator for SimulationIter<'a> {
 
         if self.finished {
             self.emitted = true;
-            return Some(self.simulation.positions().collect());
+            Some(self.simulation.positions().collect())
         } else {
             self.simulation.tick(1);
             self.finished = self.simulation.is_finished();
@@ -165,12 +197,21 @@ impl Simulation {
 
     /// Advances the simulation by a number of iterations.
     pub fn tick(&mut self, iterations: usize) {
+        let mut before = Vec::new();
         for _ in 0..iterations {
             self.alpha += (self.alpha_target - self.alpha) * self.alpha_decay;
 
+            if self.mobility.is_some() {
+                snapshot(&self.particles, &mut before);
+            }
+
             for force in &mut self.forces.values_mut() {
                 match force {
+                    Force::Anchor(a) => a.force(self.alpha, &mut self.particles),
+                    Force::AngularResolution(a) => a.force(self.alpha, &mut self.particles),
+                    Force::Bundle(b) => b.force(self.alpha, &mut self.particles),
                     Force::Collide(c) => c.force(&mut self.random, &mut self.particles),
+                    Force::CollideRect(c) => c.force(&mut self.random, &mut self.particles),
                     Force::Center(c) => c.force(&mut self.particles),
                     Force::PositionX(p) => p.force(self.alpha, &mut self.particles),
                     Force::PositionY(p) => p.force(self.alpha, &mut self.particles),
@@ -178,12 +219,42 @@ impl Simulation {
                     Force::ManyBody(m) => {
                         m.force(self.alpha, &mut self.random, &mut self.particles);
                     }
+                    Force::Obstacles(o) => o.force(&mut self.random, &mut self.particles),
+                    Force::Wells(w) => w.force(self.alpha, &mut self.particles),
+                }
+            }
+
+            if let Some(mobility) = &self.mobility {
+                // Some forces, like `Center`, move the particles directly.
+                restrain(&mut self.particles, &before, mobility);
+                for (n, m) in self.particles.iter_mut().zip(mobility) {
+                    n.vx *= m;
+                    n.vy *= m;
                 }
             }
 
             for n in &mut self.particles {
                 n.apply_velocities(self.velocity_decay);
             }
+
+            // Gauss-Seidel style projection onto the constraint set.
+            if !self.constraints.is_empty() {
+                for _ in 0..self.constraint_iterations {
+                    if self.mobility.is_some() {
+                        snapshot(&self.particles, &mut before);
+                    }
+                    for constraint in &self.constraints {
+                        constraint.project(&mut self.particles);
+                    }
+                    if let Some(mobility) = &self.mobility {
+                        restrain(&mut self.particles, &before, mobility);
+                    }
+                }
+            }
+
+            if self.is_finished() {
+                self.mobility = None;
+            }
         }
     }
 
@@ -214,6 +285,40 @@ impl Simulation {
         self
     }
 
+    /// Adds a [`Constraint`], which is enforced after every tick.
+    pub fn add_constraint(mut self, constraint: Constraint) -> Self {
+        self.constraints.push(constraint);
+        self
+    }
+
+    /// Removes all constraints from the simulation.
+    pub fn clear_constraints(&mut self) {
+        self.constraints.clear();
+    }
+
+    /// Adds new nodes to a simulation that has already been laid out, see [`Incremental`].
+    ///
+    /// The forces hold data for each particle, so they are removed and have to be added to the
+    /// returned [`SimulationExtension`] again, before it can be built into a simulation. Until
+    /// that simulation has finished, only the new nodes and their surroundings can move.
+    pub fn extend<N>(
+        mut self,
+        nodes: impl IntoIterator<Item = N>,
+        incremental: Incremental,
+    ) -> SimulationExtension
+    where
+        N: Into<Node>,
+    {
+        self.alpha = self.alpha.max(incremental.reheat_alpha());
+
+        let nodes = nodes.into_iter().map(Into::into).collect();
+        let mobility = incremental.extend(&mut self.particles, nodes, &mut self.random);
+
+        self.mobility = Some(mobility);
+        self.forces.clear();
+        SimulationExtension { simulation: self }
+    }
+
     /// Removes a force from the simulation.
     ///
     /// Returns `true` if the force was removed, `false` otherwise.
@@ -238,9 +343,52 @@ impl Simulation {
     }
 }
 
+/// Stores the current positions of the `particles` in `positions`.
+fn snapshot(particles: &[Particle], positions: &mut Vec<[f64; 2]>) {
+    positions.clear();
+    positions.extend(particles.iter().map(|n| [n.x, n.y]));
+}
+
+/// Scales how far each particle has moved away from its previous position by its mobility.
+fn restrain(particles: &mut [Particle], previous: &[[f64; 2]], mobility: &[f64]) {
+    for ((n, &[x, y]), m) in particles.iter_mut().zip(previous).zip(mobility) {
+        n.x = x + (n.x - x) * m;
+        n.y = y + (n.y - y) * m;
+    }
+}
+
+/// A [`Simulation`] that has been extended with new nodes, but has no forces yet.
+///
+/// Created by [`Simulation::extend`].
+pub struct SimulationExtension {
+    simulation: Simulation,
+}
+
+impl SimulationExtension {
+    /// Adds a force to the extended simulation, see [`Simulation::add_force`].
+    pub fn add_force(mut self, name: impl AsRef<str>, force: impl ForceBuilder) -> Self {
+        self.simulation = self.simulation.add_force(name, force);
+        self
+    }
+
+    /// Returns the names of the forces that have been added again.
+    pub fn forces(&self) -> impl Iterator<Item = &str> {
+        self.simulation.forces()
+    }
+
+    /// Returns the positions of the particles, including the new nodes.
+    pub fn positions(&self) -> impl Iterator<Item = [f64; 2]> + '_ {
+        self.simulation.positions()
+    }
+
+    pub fn build(self) -> Simulation {
+        self.simulation
+    }
+}
+
 #[cfg(test)]
 mod test {
-    use crate::{ManyBody, PositionX, PositionY};
+    use crate::{Axis, Center, Link, ManyBody, PositionX, PositionY};
 
     use super::*;
 
@@ -271,6 +419,57 @@ mod test {
         approx::assert_abs_diff_eq!(positions[3][1], 0.0, epsilon = 0.0001);
     }
 
+    #[test]
+    fn extends_simulation_locally() {
+        let links = [(0, 1), (1, 2), (2, 3), (3, 4)];
+        let mut simulation = SimulationBuilder::default()
+            .build(vec![Node::default(); 4])
+            .add_force("link", Link::new(links[..3].iter().copied()))
+            .add_force("charge", ManyBody::new());
+        simulation.step();
+        let before = simulation.positions().collect::<Vec<_>>();
+
+        let mut simulation = simulation
+            .extend([Node::default()], Incremental::new(links))
+            .add_force("link", Link::new(links))
+            .add_force("charge", ManyBody::new())
+            .build();
+        assert_eq!(simulation.forces().collect::<Vec<_>>(), ["charge", "link"]);
+
+        let [x, y] = simulation.positions().last().unwrap();
+        let [px, py] = before[3];
+        assert!((x - px).abs() <= 1.0 && (y - py).abs() <= 1.0);
+
+        simulation.step();
+        let after = simulation.positions().collect::<Vec<_>>();
+        assert_eq!(after[..3], before[..3]);
+        assert_ne!(after[3], before[3]);
+        assert!(simulation.mobility.is_none());
+    }
+
+    #[test]
+    fn keeps_frozen_nodes_in_place() {
+        let links = [(0, 1), (1, 2), (2, 3)];
+        let mut simulation = SimulationBuilder::default()
+            .build(vec![Node::default(); 3])
+            .add_force("link", Link::new(links[..2].iter().copied()))
+            .add_force("center", Center::new())
+            .add_constraint(Constraint::alignment(Axis::Y, [0, 2]));
+        simulation.step();
+        let before = simulation.positions().collect::<Vec<_>>();
+
+        let mut simulation = simulation
+            .extend([Node::default()], Incremental::new(links))
+            .add_force("link", Link::new(links))
+            .add_force("center", Center::new())
+            .build();
+        simulation.step();
+
+        let after = simulation.positions().collect::<Vec<_>>();
+        assert_eq!(after[..2], before[..2]);
+        assert_ne!(after[2], before[2]);
+    }
+
     #[test]
     fn prevent_crash_for_large_values() {
         let mut simulation = SimulationBuilder::default()
@@ -283,6 +482,10 @@ mod test {
             // This force should send the nodes flying away from each other indefinetly.
             .add_force("charge", ManyBody::default().strength(f64::MIN));
 
-        let _ = simulation.iter().last().unwrap();
+        let positions = simulation.iter().last().unwrap();
+        assert!(
+            positions.iter().flatten().all(|v| v.is_finite()),
+            "{positions:?}"
+        );
     }
 }
@@ -0,0 +1,139 @@
+use crate::quadtree::{Point, Quadtree};
+
+use super::particle::ParticleIndex;
+
+/// A quadtree over the particles that is kept up to date across ticks.
+///
+/// The tree is always the same as one that is built from scratch, so the forces behave exactly as
+/// if they rebuilt it on every tick. Particles are only re-bucketed when they leave the cell of
+/// their leaf. The tree is rebuilt, reusing its allocations, when its extent changes.
+#[derive(Clone, Debug)]
+pub(super) struct SpatialIndex<Q: Default> {
+    tree: Quadtree<Q, ParticleIndex>,
+    positions: Vec<[f64; 2]>,
+    next: Vec<[f64; 2]>,
+}
+
+impl<Q: Default> Default for SpatialIndex<Q> {
+    fn default() -> Self {
+        Self {
+            tree: Quadtree::default(),
+            positions: Vec::new(),
+            next: Vec::new(),
+        }
+    }
+}
+
+impl<Q: Default> SpatialIndex<Q> {
+    /// Moves the particles, given in the order of their index, to their new positions.
+    ///
+    /// Returns the tree together with the new positions.
+    pub fn update<P: Point>(
+        &mut self,
+        points: impl IntoIterator<Item = P>,
+    ) -> (&mut Quadtree<Q, ParticleIndex>, &[[f64; 2]]) {
+        let mut min = [f64::INFINITY; 2];
+        let mut max = [f64::NEG_INFINITY; 2];
+        self.next.clear();
+        for point in points {
+            let (x, y) = (point.x(), point.y());
+            if x.is_finite() && y.is_finite() {
+                min = [min[0].min(x), min[1].min(y)];
+                max = [max[0].max(x), max[1].max(y)];
+            }
+            self.next.push([x, y]);
+        }
+
+        // This is the extent that `add_all` would cover for the new positions.
+        let extent = Quadtree::<(), ()>::with_extent(min, max).extent();
+        let relocated = self.next.len() == self.positions.len()
+            && self.tree.extent() == extent
+            && self.relocate();
+
+        if !relocated {
+            self.tree.clear();
+            self.tree.add_all(
+                self.next
+                    .iter()
+                    .enumerate()
+                    .map(|(i, &[x, y])| (x, y, i.into())),
+            );
+        }
+
+        std::mem::swap(&mut self.positions, &mut self.next);
+        (&mut self.tree, &self.positions)
+    }
+
+    /// Moves the particles from their previous to their next positions within the tree.
+    ///
+    /// Returns `false` if a particle would join other points at the same position. Those share a
+    /// leaf in the order of their index, which relocating could not preserve.
+    fn relocate(&mut self) -> bool {
+        // Like in `add_all`, particles with non-finite positions are left out of the tree.
+        let finite = |x: f64, y: f64| x.is_finite() && y.is_finite();
+        for (i, (&[x, y], &[to_x, to_y])) in self.positions.iter().zip(&self.next).enumerate() {
+            if (x, y) == (to_x, to_y) || !finite(to_x, to_y) {
+                if finite(x, y) && !finite(to_x, to_y) {
+                    self.tree.remove(x, y, &i.into());
+                }
+                continue;
+            }
+
+            let occupied = matches!(
+                self.tree.find(to_x, to_y, f64::INFINITY),
+                Some((px, py, _)) if px == to_x && py == to_y
+            );
+            if occupied {
+                return false;
+            }
+
+            if finite(x, y) {
+                self.tree.relocate(x, y, to_x, to_y, i.into());
+            } else {
+                self.tree.insert(to_x, to_y, i.into());
+            }
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod test {
+    type SpatialIndex = super::SpatialIndex<()>;
+
+    #[test]
+    fn follows_moving_particles() {
+        let mut index = SpatialIndex::default();
+        index.update([[0.0, 0.0], [10.0, 10.0], [3.0, 7.0]]);
+        let (tree, positions) = index.update([[1.0, 0.5], [9.0, 10.0], [12.0, -4.0]]);
+        assert_eq!(positions[2], [12.0, -4.0]);
+
+        let mut points = tree
+            .data()
+            .map(|(x, y, &i)| (usize::from(i), x, y))
+            .collect::<Vec<_>>();
+        points.sort_by_key(|&(i, ..)| i);
+        assert_eq!(points, [(0, 1.0, 0.5), (1, 9.0, 10.0), (2, 12.0, -4.0)]);
+    }
+
+    #[test]
+    fn rebuilds_after_contracting() {
+        let mut index = SpatialIndex::default();
+        index.update([[0.0, 0.0], [100.0, 100.0]]);
+        let (tree, _) = index.update([[0.0, 0.0], [1.0, 1.0]]);
+        assert_eq!(tree.extent(), ([0.0, 0.0], [2.0, 2.0]));
+    }
+
+    #[test]
+    fn keeps_coincident_particles_in_order() {
+        let mut index = SpatialIndex::default();
+        index.update([[1.0, 1.0], [5.0, 5.0], [0.0, 0.0], [10.0, 10.0]]);
+        let (tree, _) = index.update([[5.0, 5.0], [5.0, 5.0], [0.0, 0.0], [10.0, 10.0]]);
+
+        let order = tree
+            .data()
+            .map(|(.., &i)| usize::from(i))
+            .collect::<Vec<_>>();
+        assert_eq!(order, [2, 0, 1, 3]);
+    }
+}
@@ -0,0 +1,231 @@
+use super::{
+    particle::Particle,
+    simulation::{Force, ForceBuilder},
+};
+
+/// Describes how the pull of a [`Well`] decays with the distance `d` to its center.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Falloff {
+    /// Decays as `1 - d / radius`, which is constant if the radius is unbounded.
+    #[default]
+    Linear,
+    /// Decays as `1 / d`.
+    Inverse,
+    /// Decays as `1 / d²`.
+    InverseSquare,
+}
+
+/// A point that attracts (positive strength) or repels (negative strength) particles.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Well {
+    x: f64,
+    y: f64,
+    strength: f64,
+    radius: f64,
+    falloff: Falloff,
+}
+
+impl Well {
+    /// Creates a well at the given position.
+    ///
+    /// The radius of influence is unbounded by default, so that a well with the default
+    /// [`Falloff::Linear`] pulls equally hard at every distance.
+    pub fn new(x: f64, y: f64) -> Self {
+        Self {
+            x,
+            y,
+            strength: 1.0,
+            radius: f64::INFINITY,
+            falloff: Falloff::default(),
+        }
+    }
+
+    pub fn strength(mut self, strength: f64) -> Self {
+        self.strength = strength;
+        self
+    }
+
+    /// Sets the radius of influence, beyond which the well has no effect.
+    pub fn radius(mut self, radius: f64) -> Self {
+        self.radius = radius;
+        self
+    }
+
+    pub fn falloff(mut self, falloff: Falloff) -> Self {
+        self.falloff = falloff;
+        self
+    }
+
+    /// Computes the magnitude of the pull at distance `d`.
+    fn magnitude(&self, d: f64, distance_min: f64) -> f64 {
+        if d > self.radius {
+            return 0.0;
+        }
+
+        let d = d.max(distance_min);
+        let decay = match self.falloff {
+            Falloff::Linear if self.radius.is_finite() => 1.0 - d / self.radius,
+            Falloff::Linear => 1.0,
+            Falloff::Inverse => 1.0 / d,
+            Falloff::InverseSquare => 1.0 / (d * d),
+        };
+        self.strength * decay.max(0.0)
+    }
+}
+
+/// Pulls particles towards or pushes them away from any number of [`Well`]s.
+pub struct Wells {
+    wells: Vec<Well>,
+    distance_min: f64,
+    assignment_fn: Option<Box<dyn Fn(usize) -> Vec<usize>>>,
+}
+
+impl Default for Wells {
+    fn default() -> Self {
+        Self {
+            wells: Vec::new(),
+            distance_min: 1.0,
+            assignment_fn: None,
+        }
+    }
+}
+
+impl Wells {
+    pub fn new(wells: impl IntoIterator<Item = Well>) -> Self {
+        Self {
+            wells: wells.into_iter().collect(),
+            ..Default::default()
+        }
+    }
+
+    pub fn well(mut self, well: Well) -> Self {
+        self.wells.push(well);
+        self
+    }
+
+    /// Sets the distance below which the pull of inverse falloffs stops growing.
+    pub fn distance_min(mut self, distance_min: f64) -> Self {
+        self.distance_min = distance_min;
+        self
+    }
+
+    /// Assigns particles to the wells, given by their index, that affect them.
+    ///
+    /// By default, every particle is affected by all wells. Adding the force to a simulation
+    /// panics if a particle is assigned to a well that does not exist.
+    pub fn assign<F>(mut self, f: F) -> Self
+    where
+        F: Fn(usize) -> Vec<usize> + 'static,
+    {
+        self.assignment_fn = Some(Box::new(f));
+        self
+    }
+}
+
+impl ForceBuilder for Wells {
+    fn initialize(self, particles: &[Particle]) -> Force {
+        let assignments = particles
+            .iter()
+            .map(|n| match &self.assignment_fn {
+                Some(f) => {
+                    let wells = f(n.index.into());
+                    if let Some(w) = wells.iter().find(|&&w| w >= self.wells.len()) {
+                        panic!(
+                            "particle {} is assigned to well {w}, but there are only {} wells",
+                            usize::from(n.index),
+                            self.wells.len()
+                        );
+                    }
+                    wells
+                }
+                None => (0..self.wells.len()).collect(),
+            })
+            .collect();
+
+        Force::Wells(WellsForce {
+            wells: self.wells,
+            distance_min: self.distance_min,
+            assignments,
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct WellsForce {
+    wells: Vec<Well>,
+    distance_min: f64,
+    assignments: Vec<Vec<usize>>,
+}
+
+impl WellsForce {
+    pub fn force(&self, alpha: f64, particles: &mut [Particle]) {
+        for node in particles.iter_mut() {
+            for &w in &self.assignments[usize::from(node.index)] {
+                let well = &self.wells[w];
+                let x = well.x - node.x;
+                let y = well.y - node.y;
+                let l = x.hypot(y);
+                if l == 0.0 {
+                    continue;
+                }
+
+                let m = well.magnitude(l, self.distance_min) * alpha / l;
+                node.vx += x * m;
+                node.vy += y * m;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Node, SimulationBuilder};
+
+    use super::*;
+
+    #[test]
+    fn computes_falloff() {
+        let well = Well::new(0.0, 0.0).strength(2.0).radius(10.0);
+        assert_eq!(well.magnitude(5.0, 1.0), 1.0);
+        assert_eq!(well.magnitude(11.0, 1.0), 0.0);
+
+        let well = well.falloff(Falloff::Inverse);
+        assert_eq!(well.magnitude(4.0, 1.0), 0.5);
+        assert_eq!(well.magnitude(0.5, 1.0), 2.0);
+
+        let well = well.falloff(Falloff::InverseSquare);
+        assert_eq!(well.magnitude(2.0, 1.0), 0.5);
+    }
+
+    #[test]
+    #[should_panic(expected = "assigned to well 1")]
+    fn rejects_missing_wells() {
+        let _ = SimulationBuilder::default()
+            .build([Node::default()])
+            .add_force(
+                "wells",
+                Wells::new([Well::new(0.0, 0.0)]).assign(|_| vec![1]),
+            );
+    }
+
+    #[test]
+    fn pulls_particles_to_their_assigned_wells() {
+        let mut simulation = SimulationBuilder::default()
+            .build([
+                Node::default().position(0.0, 0.0),
+                Node::default().position(0.0, 1.0),
+            ])
+            .add_force(
+                "wells",
+                Wells::default()
+                    .well(Well::new(-50.0, 0.0).strength(0.5))
+                    .well(Well::new(50.0, 0.0).strength(0.5))
+                    .assign(|i| vec![i]),
+            );
+
+        let positions = simulation.iter().last().unwrap();
+
+        assert!(positions[0][0] < -40.0);
+        assert!(positions[1][0] > 40.0);
+    }
+}
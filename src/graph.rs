@@ -0,0 +1,115 @@
+use std::collections::VecDeque;
+
+/// An undirected graph, stored as adjacency lists.
+#[derive(Clone, Debug)]
+pub(crate) struct Graph {
+    neighbors: Vec<Vec<usize>>,
+}
+
+impl Graph {
+    /// Builds the graph from a list of links. Self-loops are ignored.
+    ///
+    /// Panics if a link refers to a node that does not exist.
+    pub fn from_links(len: usize, links: impl IntoIterator<Item = (usize, usize)>) -> Self {
+        let mut neighbors = vec![Vec::new(); len];
+        for (source, target) in links {
+            assert!(
+                source < len && target < len,
+                "link ({source}, {target}) refers to a node that does not exist, there are {len} nodes"
+            );
+            if source != target {
+                neighbors[source].push(target);
+                neighbors[target].push(source);
+            }
+        }
+        Self { neighbors }
+    }
+
+    pub fn len(&self) -> usize {
+        self.neighbors.len()
+    }
+
+    pub fn neighbors(&self, node: usize) -> &[usize] {
+        &self.neighbors[node]
+    }
+
+    /// Computes the centroid of the neighbors of `node` that have a position.
+    ///
+    /// Returns `None` if none of them has one.
+    pub fn neighbor_centroid(
+        &self,
+        node: usize,
+        position: impl Fn(usize) -> Option<[f64; 2]>,
+    ) -> Option<[f64; 2]> {
+        let mut sum = [0.0, 0.0];
+        let mut count = 0;
+        for [x, y] in self.neighbors[node].iter().filter_map(|&n| position(n)) {
+            sum = [sum[0] + x, sum[1] + y];
+            count += 1;
+        }
+        (count > 0).then(|| [sum[0] / count as f64, sum[1] / count as f64])
+    }
+
+    /// Splits the nodes into connected components, in the order of their smallest node.
+    pub fn components(&self) -> Vec<Vec<usize>> {
+        let mut visited = vec![false; self.len()];
+        let mut components = Vec::new();
+        for start in 0..self.len() {
+            if visited[start] {
+                continue;
+            }
+            visited[start] = true;
+            let mut component = vec![start];
+            let mut next = 0;
+            while let Some(&node) = component.get(next) {
+                next += 1;
+                for &n in &self.neighbors[node] {
+                    if !visited[n] {
+                        visited[n] = true;
+                        component.push(n);
+                    }
+                }
+            }
+            components.push(component);
+        }
+        components
+    }
+
+    /// Computes the number of hops from `source` to all nodes, using a breadth-first search.
+    ///
+    /// Unreachable nodes have a distance of `f64::INFINITY`.
+    pub fn distances_from(&self, source: usize) -> Vec<f64> {
+        let mut distances = vec![f64::INFINITY; self.len()];
+        let mut queue = VecDeque::from([source]);
+        distances[source] = 0.0;
+
+        while let Some(node) = queue.pop_front() {
+            for &n in &self.neighbors[node] {
+                if distances[n].is_infinite() {
+                    distances[n] = distances[node] + 1.0;
+                    queue.push_back(n);
+                }
+            }
+        }
+        distances
+    }
+
+    /// Computes the all-pairs shortest-path distances in hops.
+    pub fn distances(&self) -> Vec<Vec<f64>> {
+        (0..self.len()).map(|i| self.distances_from(i)).collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn computes_shortest_paths() {
+        let graph = Graph::from_links(5, [(0, 1), (1, 2), (2, 0), (2, 3)]);
+        assert_eq!(
+            graph.distances_from(0),
+            vec![0.0, 1.0, 1.0, 2.0, f64::INFINITY]
+        );
+    }
+}
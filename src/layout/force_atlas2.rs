@@ -0,0 +1,416 @@
+use crate::{
+    force::{jiggle::jiggle, particle::Particle, Node},
+    lcg::Lcg,
+    quadtree::{Aggregator, Entry, Quadtree, Visit},
+};
+
+use super::initial_particles;
+
+/// Creates a [`ForceAtlas2`] layout.
+///
+/// The defaults follow the implementation in Gephi, as described in Jacomy et al.,
+/// "ForceAtlas2, a Continuous Graph Layout Algorithm for Handy Network Visualization" (2014).
+#[derive(Clone, Debug)]
+pub struct ForceAtlas2Builder {
+    links: Vec<(usize, usize)>,
+    scaling: f64,
+    gravity: f64,
+    strong_gravity: bool,
+    lin_log: bool,
+    dissuade_hubs: bool,
+    jitter_tolerance: f64,
+    theta: f64,
+}
+
+impl ForceAtlas2Builder {
+    /// Creates the builder for the given links.
+    ///
+    /// Each link attracts its endpoints, and the degrees it induces set the mass of the nodes.
+    pub fn new(links: impl IntoIterator<Item = (usize, usize)>) -> Self {
+        Self {
+            links: links.into_iter().collect(),
+            scaling: 2.0,
+            gravity: 1.0,
+            strong_gravity: false,
+            lin_log: false,
+            dissuade_hubs: false,
+            jitter_tolerance: 1.0,
+            theta: 1.2,
+        }
+    }
+
+    /// Sets the scaling of the repulsion between nodes.
+    pub fn scaling(mut self, scaling: f64) -> Self {
+        self.scaling = scaling;
+        self
+    }
+
+    /// Sets the strength of the gravity that pulls nodes towards the origin.
+    pub fn gravity(mut self, gravity: f64) -> Self {
+        self.gravity = gravity;
+        self
+    }
+
+    /// Makes the gravity grow linearly with the distance to the origin.
+    pub fn strong_gravity(mut self, strong_gravity: bool) -> Self {
+        self.strong_gravity = strong_gravity;
+        self
+    }
+
+    /// Uses the logarithm of the distance for the attraction, which results in tighter clusters.
+    pub fn lin_log(mut self, lin_log: bool) -> Self {
+        self.lin_log = lin_log;
+        self
+    }
+
+    /// Divides the attraction by the degree of the source node, which pushes hubs to the periphery.
+    pub fn dissuade_hubs(mut self, dissuade_hubs: bool) -> Self {
+        self.dissuade_hubs = dissuade_hubs;
+        self
+    }
+
+    /// Sets how much swinging is tolerated. Lower values result in more precision.
+    pub fn jitter_tolerance(mut self, jitter_tolerance: f64) -> Self {
+        self.jitter_tolerance = jitter_tolerance;
+        self
+    }
+
+    /// Sets the accuracy of the Barnes–Hut approximation of the repulsion.
+    pub fn theta(mut self, theta: f64) -> Self {
+        self.theta = theta;
+        self
+    }
+
+    pub fn build<N>(self, nodes: impl IntoIterator<Item = N>) -> ForceAtlas2
+    where
+        N: Into<Node>,
+    {
+        let particles = initial_particles(nodes, None);
+
+        let mut masses = vec![1.0; particles.len()];
+        for &(source, target) in &self.links {
+            masses[source] += 1.0;
+            masses[target] += 1.0;
+        }
+
+        let outbound_compensation = if self.dissuade_hubs && !masses.is_empty() {
+            masses.iter().sum::<f64>() / masses.len() as f64
+        } else {
+            1.0
+        };
+
+        ForceAtlas2 {
+            forces: vec![[0.0; 2]; particles.len()],
+            previous: vec![[0.0; 2]; particles.len()],
+            particles,
+            masses,
+            outbound_compensation,
+            speed: 1.0,
+            speed_efficiency: 1.0,
+            random: Lcg::default(),
+            settings: self,
+        }
+    }
+}
+
+/// A ForceAtlas2 layout, which is advanced similarly to a [`Simulation`](crate::Simulation).
+///
+/// In contrast to the simulation, there is no cooling schedule. Instead, the speed of every node
+/// is adapted based on how much it swings.
+pub struct ForceAtlas2 {
+    settings: ForceAtlas2Builder,
+    particles: Vec<Particle>,
+    masses: Vec<f64>,
+    outbound_compensation: f64,
+    forces: Vec<[f64; 2]>,
+    previous: Vec<[f64; 2]>,
+    speed: f64,
+    speed_efficiency: f64,
+    random: Lcg,
+}
+
+#[derive(Default)]
+struct Mass {
+    x: f64,
+    y: f64,
+    mass: f64,
+}
+
+/// Sums up the masses of the particles at their center of mass.
+struct Masses<'a>(&'a [f64]);
+
+impl Aggregator<Mass, usize> for Masses<'_> {
+    fn leaf<'a>(&self, x: f64, y: f64, points: impl Iterator<Item = &'a usize>) -> Mass {
+        let mass = points.map(|&i| self.0[i]).sum();
+        Mass { x, y, mass }
+    }
+
+    fn combine(&self, children: [Option<&Mass>; 4]) -> Mass {
+        let mut result = Mass::default();
+        for q in children.into_iter().flatten() {
+            result.mass += q.mass;
+            result.x += q.mass * q.x;
+            result.y += q.mass * q.y;
+        }
+        result.x /= result.mass;
+        result.y /= result.mass;
+        result
+    }
+}
+
+impl ForceAtlas2 {
+    /// Advances the layout by a number of iterations.
+    pub fn tick(&mut self, iterations: usize) {
+        for _ in 0..iterations {
+            std::mem::swap(&mut self.forces, &mut self.previous);
+            self.forces.fill([0.0; 2]);
+
+            self.repulsion();
+            self.gravity();
+            self.attraction();
+            self.apply();
+        }
+    }
+
+    /// Returns the positions of the nodes.
+    ///
+    /// The ordering of the nodes is stable, so the order of the positions will be the same as
+    /// initially supplied.
+    pub fn positions(&self) -> impl Iterator<Item = [f64; 2]> + '_ {
+        self.particles.iter().map(|n: &Particle| [n.x, n.y])
+    }
+
+    /// Approximates the degree-weighted repulsion with a Barnes–Hut quadtree.
+    fn repulsion(&mut self) {
+        let masses = &self.masses;
+        let particles = &self.particles;
+        let mut tree = Quadtree::<Mass, usize>::from_points_with(
+            0..particles.len(),
+            |&i| particles[i].x,
+            |&i| particles[i].y,
+        );
+        tree.aggregate(&Masses(masses));
+
+        let scaling = self.settings.scaling;
+        let theta = self.settings.theta;
+        let random = &mut self.random;
+        for (i, node) in self.particles.iter().enumerate() {
+            let force = &mut self.forces[i];
+            let mass = masses[i];
+            tree.visit(|quad| {
+                let mut x = node.x - quad.value().x;
+                let mut y = node.y - quad.value().y;
+                let mut l = x.hypot(y);
+                let w = quad.extent().x1 - quad.extent().x0;
+
+                match quad.inner() {
+                    Entry::Internal { .. } if l * theta <= w => Visit::Continue,
+                    Entry::Internal { .. } => {
+                        let f = scaling * mass * quad.value().mass / (l * l);
+                        force[0] += x * f;
+                        force[1] += y * f;
+                        Visit::Skip
+                    }
+                    Entry::Leaf { data, others, .. } => {
+                        let other = std::iter::once(data)
+                            .chain(others.unwrap_or_default())
+                            .filter(|&&j| j != i)
+                            .map(|&j| masses[j])
+                            .sum::<f64>();
+                        if other > 0.0 {
+                            // Coincident nodes repel each other as if they were a unit distance
+                            // apart, in a random direction.
+                            if l == 0.0 {
+                                (x, y) = random_direction(random);
+                                l = 1.0;
+                            }
+                            let f = scaling * mass * other / (l * l);
+                            force[0] += x * f;
+                            force[1] += y * f;
+                        }
+                        Visit::Skip
+                    }
+                }
+            });
+        }
+    }
+
+    /// Pulls the nodes towards the origin.
+    ///
+    /// Like in Gephi, the gravity does not depend on the scaling of the repulsion in either mode.
+    fn gravity(&mut self) {
+        for ((node, force), mass) in self
+            .particles
+            .iter()
+            .zip(&mut self.forces)
+            .zip(&self.masses)
+        {
+            let l = node.x.hypot(node.y);
+            if l == 0.0 {
+                continue;
+            }
+            let f = if self.settings.strong_gravity {
+                mass * self.settings.gravity
+            } else {
+                mass * self.settings.gravity / l
+            };
+            force[0] -= node.x * f;
+            force[1] -= node.y * f;
+        }
+    }
+
+    fn attraction(&mut self) {
+        let coefficient = self.outbound_compensation;
+        for &(source, target) in &self.settings.links {
+            if source == target {
+                continue;
+            }
+
+            let x = self.particles[source].x - self.particles[target].x;
+            let y = self.particles[source].y - self.particles[target].y;
+            let l = x.hypot(y);
+            if l == 0.0 {
+                continue;
+            }
+
+            let mut f = if self.settings.lin_log {
+                -coefficient * l.ln_1p() / l
+            } else {
+                -coefficient
+            };
+            if self.settings.dissuade_hubs {
+                f /= self.masses[source];
+            }
+
+            self.forces[source][0] += x * f;
+            self.forces[source][1] += y * f;
+            self.forces[target][0] -= x * f;
+            self.forces[target][1] -= y * f;
+        }
+    }
+
+    /// Adapts the global speed based on swinging and traction, and moves the nodes.
+    fn apply(&mut self) {
+        let n = self.particles.len() as f64;
+
+        let mut total_swinging = 0.0;
+        let mut total_traction = 0.0;
+        for ((&[fx, fy], &[px, py]), mass) in
+            self.forces.iter().zip(&self.previous).zip(&self.masses)
+        {
+            total_swinging += mass * (fx - px).hypot(fy - py);
+            total_traction += mass * (fx + px).hypot(fy + py) / 2.0;
+        }
+
+        if total_swinging == 0.0 || total_traction == 0.0 {
+            return;
+        }
+
+        let estimated_jitter = 0.05 * n.sqrt();
+        let min_jitter = estimated_jitter.sqrt();
+        let max_jitter = 10.0;
+        let mut jitter = self.settings.jitter_tolerance
+            * f64::max(
+                min_jitter,
+                f64::min(max_jitter, estimated_jitter * total_traction / (n * n)),
+            );
+
+        let min_speed_efficiency = 0.05;
+        if total_swinging / total_traction > 2.0 {
+            if self.speed_efficiency > min_speed_efficiency {
+                self.speed_efficiency *= 0.5;
+            }
+            jitter = jitter.max(self.settings.jitter_tolerance);
+        }
+
+        let target_speed = jitter * self.speed_efficiency * total_traction / total_swinging;
+
+        if total_swinging > jitter * total_traction {
+            if self.speed_efficiency > min_speed_efficiency {
+                self.speed_efficiency *= 0.7;
+            }
+        } else if self.speed < 1000.0 {
+            self.speed_efficiency *= 1.3;
+        }
+
+        let max_rise = 0.5;
+        self.speed += f64::min(target_speed - self.speed, max_rise * self.speed);
+
+        for (i, node) in self.particles.iter_mut().enumerate() {
+            let [fx, fy] = self.forces[i];
+            let [px, py] = self.previous[i];
+            let swinging = self.masses[i] * (fx - px).hypot(fy - py);
+            let factor = self.speed / (1.0 + (self.speed * swinging).sqrt());
+
+            if node.fx.is_none() {
+                node.x += fx * factor;
+            }
+            if node.fy.is_none() {
+                node.y += fy * factor;
+            }
+        }
+    }
+}
+
+/// Returns a random unit vector.
+fn random_direction(random: &mut Lcg) -> (f64, f64) {
+    loop {
+        let (x, y) = (jiggle(random), jiggle(random));
+        let l = x.hypot(y);
+        if l > 0.0 {
+            return (x / l, y / l);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::layout::distance;
+
+    #[test]
+    fn separates_clusters() {
+        // Two triangles that are connected by a single edge.
+        let links = [(0, 1), (1, 2), (2, 0), (3, 4), (4, 5), (5, 3), (2, 3)];
+        let mut layout = ForceAtlas2Builder::new(links)
+            .lin_log(true)
+            .build((0..6).map(|_| Node::default()));
+        layout.tick(500);
+
+        let positions = layout.positions().collect::<Vec<_>>();
+        assert_eq!(positions.len(), 6);
+        assert!(positions.iter().flatten().all(|v| v.is_finite()));
+        assert!(distance(positions[0], positions[1]) < distance(positions[0], positions[4]));
+        assert!(distance(positions[3], positions[5]) < distance(positions[5], positions[1]));
+    }
+
+    #[test]
+    fn separates_coincident_nodes() {
+        let mut layout = ForceAtlas2Builder::new([]).build([
+            Node::default().position(1.0, 1.0),
+            Node::default().position(1.0, 1.0),
+        ]);
+        layout.tick(200);
+
+        let positions = layout.positions().collect::<Vec<_>>();
+        assert!(positions.iter().flatten().all(|v| v.is_finite()));
+        assert!(distance(positions[0], positions[1]) > 1.0, "{positions:?}");
+    }
+
+    #[test]
+    fn respects_fixed_positions() {
+        let mut layout = ForceAtlas2Builder::new([(0, 1), (1, 2)])
+            .strong_gravity(true)
+            .dissuade_hubs(true)
+            .build([
+                Node::default().fixed_position(100.0, 100.0),
+                Node::default(),
+                Node::default(),
+            ]);
+        layout.tick(100);
+
+        let positions = layout.positions().collect::<Vec<_>>();
+        assert_eq!(positions[0], [100.0, 100.0]);
+        assert!(positions.iter().flatten().all(|v| v.is_finite()));
+    }
+}
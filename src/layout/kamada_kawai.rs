@@ -0,0 +1,245 @@
+use crate::force::Node;
+
+use crate::graph::Graph;
+
+use super::initial_particles;
+
+/// Lays out a graph with the spring model from Kamada and Kawai, "An Algorithm for Drawing
+/// General Undirected Graphs" (1989).
+///
+/// Every pair of nodes is connected by a spring whose length is proportional to their
+/// shortest-path distance. The energy of the system is minimized by moving one node at a
+/// time with Newton–Raphson steps, until the largest gradient falls below `epsilon`.
+///
+/// Nodes in different connected components are treated as if they were one hop further apart
+/// than the longest shortest path in the graph.
+#[derive(Clone, Debug)]
+pub struct KamadaKawai {
+    links: Vec<(usize, usize)>,
+    edge_length: f64,
+    strength: f64,
+    epsilon: f64,
+    iterations: usize,
+    warm_start: Option<Vec<[f64; 2]>>,
+}
+
+impl KamadaKawai {
+    /// Creates the layout for the given links, whose shortest paths define the ideal distances of
+    /// the springs between all pairs of nodes.
+    pub fn new(links: impl IntoIterator<Item = (usize, usize)>) -> Self {
+        Self {
+            links: links.into_iter().collect(),
+            edge_length: 30.0,
+            strength: 1.0,
+            epsilon: 1e-2,
+            iterations: 10_000,
+            warm_start: None,
+        }
+    }
+
+    /// Sets the desired length of a single edge.
+    pub fn edge_length(mut self, edge_length: f64) -> Self {
+        self.edge_length = edge_length;
+        self
+    }
+
+    /// Sets the strength of the springs, which is divided by the squared graph distance.
+    pub fn strength(mut self, strength: f64) -> Self {
+        self.strength = strength;
+        self
+    }
+
+    /// Sets the convergence threshold for the gradient of the energy.
+    pub fn epsilon(mut self, epsilon: f64) -> Self {
+        self.epsilon = epsilon;
+        self
+    }
+
+    /// Sets the maximum number of nodes that are moved before the layout stops.
+    pub fn iterations(mut self, iterations: usize) -> Self {
+        self.iterations = iterations;
+        self
+    }
+
+    /// Starts the optimization from existing positions, for example from
+    /// [`Simulation::positions`](crate::Simulation::positions).
+    pub fn warm_start(mut self, positions: impl IntoIterator<Item = [f64; 2]>) -> Self {
+        self.warm_start = Some(positions.into_iter().collect());
+        self
+    }
+
+    /// Computes the positions of the nodes, in the order in which they were given.
+    ///
+    /// Nodes that were created with [`Node::fixed_position`] are not moved.
+    pub fn layout<N>(&self, nodes: impl IntoIterator<Item = N>) -> Vec<[f64; 2]>
+    where
+        N: Into<Node>,
+    {
+        let particles = initial_particles(nodes, self.warm_start.as_deref());
+        let n = particles.len();
+
+        let mut distances = Graph::from_links(n, self.links.iter().copied()).distances();
+        let diameter = distances
+            .iter()
+            .flatten()
+            .copied()
+            .filter(|d| d.is_finite())
+            .fold(0.0, f64::max);
+        for d in distances.iter_mut().flatten() {
+            if d.is_infinite() {
+                *d = diameter + 1.0;
+            }
+        }
+
+        let springs = Springs {
+            lengths: distances
+                .iter()
+                .map(|row| row.iter().map(|d| d * self.edge_length).collect())
+                .collect(),
+            strengths: distances
+                .iter()
+                .map(|row| {
+                    row.iter()
+                        .map(|d| {
+                            if *d > 0.0 {
+                                self.strength / (d * d)
+                            } else {
+                                0.0
+                            }
+                        })
+                        .collect()
+                })
+                .collect(),
+        };
+
+        let movable = particles
+            .iter()
+            .map(|p| p.fx.is_none() && p.fy.is_none())
+            .collect::<Vec<_>>();
+        let mut positions = particles.iter().map(|p| [p.x, p.y]).collect::<Vec<_>>();
+        let mut gradients = (0..n)
+            .map(|m| springs.gradient(&positions, m))
+            .collect::<Vec<_>>();
+
+        for _ in 0..self.iterations {
+            let Some((m, delta)) = gradients
+                .iter()
+                .enumerate()
+                .filter(|&(m, _)| movable[m])
+                .map(|(m, [gx, gy])| (m, gx.hypot(*gy)))
+                .max_by(|a, b| a.1.total_cmp(&b.1))
+            else {
+                break;
+            };
+
+            if delta < self.epsilon {
+                break;
+            }
+
+            let before = positions[m];
+            // The inner loop only needs a few steps, as Newton-Raphson converges quadratically.
+            for _ in 0..100 {
+                let [gx, gy] = gradients[m];
+                if gx.hypot(gy) < self.epsilon {
+                    break;
+                }
+
+                let [a, b, c] = springs.hessian(&positions, m);
+                let determinant = a * c - b * b;
+                if determinant == 0.0 {
+                    break;
+                }
+
+                positions[m][0] += (b * gy - c * gx) / determinant;
+                positions[m][1] += (b * gx - a * gy) / determinant;
+                gradients[m] = springs.gradient(&positions, m);
+            }
+
+            // Moving `m` changes the gradients of all other nodes.
+            for i in (0..n).filter(|&i| i != m) {
+                let [ox, oy] = springs.term(positions[i], before, i, m);
+                let [nx, ny] = springs.term(positions[i], positions[m], i, m);
+                gradients[i][0] += nx - ox;
+                gradients[i][1] += ny - oy;
+            }
+        }
+
+        positions
+    }
+}
+
+struct Springs {
+    lengths: Vec<Vec<f64>>,
+    strengths: Vec<Vec<f64>>,
+}
+
+impl Springs {
+    /// The contribution of the spring between `i` and `j` to the gradient of `i`.
+    fn term(&self, [xi, yi]: [f64; 2], [xj, yj]: [f64; 2], i: usize, j: usize) -> [f64; 2] {
+        let (dx, dy) = (xi - xj, yi - yj);
+        let l = dx.hypot(dy);
+        if l == 0.0 {
+            return [0.0, 0.0];
+        }
+        let k = self.strengths[i][j];
+        let s = self.lengths[i][j] / l;
+        [k * (dx - s * dx), k * (dy - s * dy)]
+    }
+
+    fn gradient(&self, positions: &[[f64; 2]], m: usize) -> [f64; 2] {
+        (0..positions.len())
+            .filter(|&i| i != m)
+            .map(|i| self.term(positions[m], positions[i], m, i))
+            .fold([0.0, 0.0], |[ax, ay], [x, y]| [ax + x, ay + y])
+    }
+
+    /// Returns the entries `[∂²E/∂x², ∂²E/∂x∂y, ∂²E/∂y²]` of the Hessian for node `m`.
+    fn hessian(&self, positions: &[[f64; 2]], m: usize) -> [f64; 3] {
+        let [xm, ym] = positions[m];
+        let mut hessian = [0.0; 3];
+        for (i, &[xi, yi]) in positions.iter().enumerate() {
+            let (dx, dy) = (xm - xi, ym - yi);
+            let l = dx.hypot(dy);
+            if i == m || l == 0.0 {
+                continue;
+            }
+            let k = self.strengths[m][i];
+            let s = self.lengths[m][i] / (l * l * l);
+            hessian[0] += k * (1.0 - s * dy * dy);
+            hessian[1] += k * s * dx * dy;
+            hessian[2] += k * (1.0 - s * dx * dx);
+        }
+        hessian
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::layout::distance;
+
+    #[test]
+    fn preserves_graph_distances_of_a_path() {
+        let positions = KamadaKawai::new([(0, 1), (1, 2), (2, 3)])
+            .epsilon(1e-6)
+            .layout((0..4).map(|_| Node::default()));
+
+        approx::assert_abs_diff_eq!(distance(positions[0], positions[1]), 30.0, epsilon = 0.01);
+        approx::assert_abs_diff_eq!(distance(positions[1], positions[2]), 30.0, epsilon = 0.01);
+        approx::assert_abs_diff_eq!(distance(positions[0], positions[3]), 90.0, epsilon = 0.01);
+    }
+
+    #[test]
+    fn respects_fixed_positions() {
+        let positions = KamadaKawai::new([(0, 1), (1, 2), (2, 0)]).layout([
+            Node::default().fixed_position(100.0, 100.0),
+            Node::default().fixed_position(130.0, 100.0),
+            Node::default(),
+        ]);
+
+        assert_eq!(positions[0], [100.0, 100.0]);
+        assert_eq!(positions[1], [130.0, 100.0]);
+        approx::assert_abs_diff_eq!(distance(positions[0], positions[2]), 30.0, epsilon = 0.01);
+        approx::assert_abs_diff_eq!(distance(positions[1], positions[2]), 30.0, epsilon = 0.01);
+    }
+}
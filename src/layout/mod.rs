@@ -0,0 +1,49 @@
+//! Layout engines that complement the force-directed [`Simulation`](crate::Simulation).
+//!
+//! The engines accept the same node and link inputs as [`SimulationBuilder`](crate::SimulationBuilder)
+//! and [`Link`](crate::Link), and return positions in the same order as
+//! [`Simulation::positions`](crate::Simulation::positions).
+
+mod force_atlas2;
+mod kamada_kawai;
+mod multilevel;
+mod spectral;
+mod stress;
+
+pub use force_atlas2::{ForceAtlas2, ForceAtlas2Builder};
+pub use kamada_kawai::KamadaKawai;
+pub use multilevel::Multilevel;
+pub use spectral::Spectral;
+pub use stress::StressMajorization;
+
+use crate::force::{initial_position, particle::Particle, Node};
+
+/// Creates the particles for a layout, in the same way as [`SimulationBuilder::build`](crate::SimulationBuilder::build).
+///
+/// Positions from a warm start take precedence over the phyllotaxis arrangement, but not over
+/// positions that were set explicitly on a node.
+pub(crate) fn initial_particles<N>(
+    nodes: impl IntoIterator<Item = N>,
+    warm_start: Option<&[[f64; 2]]>,
+) -> Vec<Particle>
+where
+    N: Into<Node>,
+{
+    nodes
+        .into_iter()
+        .enumerate()
+        .map(|(ix, node)| {
+            node.into().build_with_pos(ix.into(), || {
+                warm_start
+                    .and_then(|positions| positions.get(ix).copied())
+                    .unwrap_or_else(|| initial_position(ix))
+            })
+        })
+        .collect()
+}
+
+/// Returns the Euclidean distance between two positions.
+#[cfg(test)]
+fn distance([ax, ay]: [f64; 2], [bx, by]: [f64; 2]) -> f64 {
+    (ax - bx).hypot(ay - by)
+}
@@ -0,0 +1,298 @@
+use crate::{force::Node, lcg::Lcg, Center, Link, ManyBody, SimulationBuilder};
+
+use crate::graph::Graph;
+
+/// Lays out large graphs by first laying out a series of coarser versions of the graph.
+///
+/// The graph is coarsened repeatedly by merging the nodes of a maximal matching. The coarsest
+/// graph is laid out with a full [`Simulation`](crate::Simulation) using the [`Link`],
+/// [`ManyBody`], and [`Center`] forces. The positions are then prolonged to the next finer
+/// level, where they are refined with a short simulation run, until the original graph is
+/// reached.
+#[derive(Clone, Debug)]
+pub struct Multilevel {
+    links: Vec<(usize, usize)>,
+    coarsest_size: usize,
+    distance: f64,
+    strength: f64,
+    refinement: usize,
+}
+
+/// One level of the coarsening hierarchy.
+#[derive(Debug)]
+struct Level {
+    links: Vec<(usize, usize)>,
+    /// The number of nodes of the original graph that each node represents.
+    weights: Vec<f64>,
+    /// Maps each node to its node in the next coarser level.
+    parents: Vec<usize>,
+}
+
+impl Multilevel {
+    /// Creates the layout for the given links, which are merged along with their endpoints while
+    /// coarsening.
+    pub fn new(links: impl IntoIterator<Item = (usize, usize)>) -> Self {
+        Self {
+            links: links.into_iter().collect(),
+            coarsest_size: 100,
+            distance: 30.0,
+            strength: -30.0,
+            refinement: 50,
+        }
+    }
+
+    /// Stops coarsening once a level has at most this many nodes.
+    pub fn coarsest_size(mut self, coarsest_size: usize) -> Self {
+        self.coarsest_size = coarsest_size;
+        self
+    }
+
+    /// Sets the distance of the links in the original graph.
+    pub fn distance(mut self, distance: f64) -> Self {
+        self.distance = distance;
+        self
+    }
+
+    /// Sets the strength of the charge of a single node in the original graph.
+    pub fn strength(mut self, strength: f64) -> Self {
+        self.strength = strength;
+        self
+    }
+
+    /// Sets the number of ticks that are used to refine the layout on each level.
+    pub fn refinement(mut self, refinement: usize) -> Self {
+        self.refinement = refinement;
+        self
+    }
+
+    /// Computes the positions of the nodes, in the order in which they were given.
+    ///
+    /// When the original graph is laid out, nodes that were created with [`Node::fixed_position`]
+    /// are fixed, and positions that were set with [`Node::position`] take precedence over the
+    /// ones from the coarser levels.
+    pub fn layout<N>(&self, nodes: impl IntoIterator<Item = N>) -> Vec<[f64; 2]>
+    where
+        N: Into<Node>,
+    {
+        let nodes = nodes.into_iter().map(Into::into).collect::<Vec<_>>();
+
+        let mut levels = vec![Level {
+            links: self.links.clone(),
+            weights: vec![1.0; nodes.len()],
+            parents: Vec::new(),
+        }];
+        loop {
+            let finest = levels.len() - 1;
+            let fine = &mut levels[finest];
+            if fine.weights.len() <= self.coarsest_size {
+                break;
+            }
+            let coarse = coarsen(fine);
+            // Stop if the matching does not shrink the graph noticeably anymore.
+            if coarse.weights.len() as f64 > 0.9 * fine.weights.len() as f64 {
+                fine.parents.clear();
+                break;
+            }
+            levels.push(coarse);
+        }
+
+        let mut random = Lcg::default();
+        let coarsest = levels.len() - 1;
+        // The original nodes are only used for the finest level, which may also be the coarsest.
+        let original = |depth: usize| (depth == 0).then_some(nodes.as_slice());
+        let mut positions = self.simulate(&levels[coarsest], None, original(coarsest));
+
+        for (depth, level) in levels[..coarsest].iter().enumerate().rev() {
+            let spread = self.distance / 10.0;
+            let prolonged = level
+                .parents
+                .iter()
+                .map(|&parent| {
+                    let [x, y] = positions[parent];
+                    let jx = random.next().unwrap_or_default() - 0.5;
+                    let jy = random.next().unwrap_or_default() - 0.5;
+                    [x + jx * spread, y + jy * spread]
+                })
+                .collect::<Vec<_>>();
+
+            positions = self.simulate(level, Some(&prolonged), original(depth));
+        }
+
+        positions
+    }
+
+    /// Lays out a single level, either from scratch or by refining prolonged positions.
+    fn simulate(
+        &self,
+        level: &Level,
+        positions: Option<&[[f64; 2]]>,
+        original: Option<&[Node]>,
+    ) -> Vec<[f64; 2]> {
+        let nodes = (0..level.weights.len()).map(|i| {
+            let node = original.map_or_else(Node::default, |o| o[i].clone());
+            match positions {
+                Some(positions) if node.explicit_position().is_none() => {
+                    let [x, y] = positions[i];
+                    node.position(x, y)
+                }
+                _ => node,
+            }
+        });
+
+        let builder = if positions.is_some() {
+            SimulationBuilder::default().with_alpha(0.3)
+        } else {
+            SimulationBuilder::default()
+        };
+
+        // Coarse nodes represent several original nodes, so we scale the forces accordingly.
+        let weights = level.weights.clone();
+        let strength = self.strength;
+        let scale = |w: f64| w.sqrt();
+        let distances = level
+            .links
+            .iter()
+            .map(|&(s, t)| {
+                self.distance * (scale(level.weights[s]) + scale(level.weights[t])) / 2.0
+            })
+            .collect::<Vec<_>>();
+
+        let mut simulation = builder
+            .build(nodes)
+            .add_force(
+                "link",
+                Link::new(level.links.iter().copied()).distance(move |_: &_, i| distances[i]),
+            )
+            .add_force(
+                "charge",
+                ManyBody::new().strength(move |_, i| strength * weights[i]),
+            )
+            .add_force("center", Center::new());
+
+        if positions.is_some() {
+            simulation.tick(self.refinement);
+        } else {
+            simulation.step();
+        }
+
+        simulation.positions().collect()
+    }
+}
+
+/// Merges the endpoints of a maximal matching, preferring edges between light nodes.
+fn coarsen(level: &mut Level) -> Level {
+    let n = level.weights.len();
+    let graph = Graph::from_links(n, level.links.iter().copied());
+
+    let mut order = (0..n).collect::<Vec<_>>();
+    order.sort_by_key(|&i| graph.neighbors(i).len());
+
+    let mut parents = vec![usize::MAX; n];
+    let mut weights = Vec::new();
+    for &i in &order {
+        if parents[i] != usize::MAX {
+            continue;
+        }
+
+        let partner = graph
+            .neighbors(i)
+            .iter()
+            .copied()
+            .filter(|&j| parents[j] == usize::MAX && j != i)
+            .min_by(|&a, &b| level.weights[a].total_cmp(&level.weights[b]));
+
+        parents[i] = weights.len();
+        let mut weight = level.weights[i];
+        if let Some(j) = partner {
+            parents[j] = weights.len();
+            weight += level.weights[j];
+        }
+        weights.push(weight);
+    }
+
+    let mut links = level
+        .links
+        .iter()
+        .map(|&(s, t)| {
+            let (s, t) = (parents[s], parents[t]);
+            (s.min(t), s.max(t))
+        })
+        .filter(|(s, t)| s != t)
+        .collect::<Vec<_>>();
+    links.sort_unstable();
+    links.dedup();
+
+    level.parents = parents;
+    Level {
+        links,
+        weights,
+        parents: Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::layout::distance;
+
+    #[test]
+    fn coarsens_by_matching() {
+        let mut level = Level {
+            links: vec![(0, 1), (1, 2), (2, 3)],
+            weights: vec![1.0; 4],
+            parents: Vec::new(),
+        };
+
+        let coarse = coarsen(&mut level);
+
+        assert_eq!(coarse.weights, vec![2.0, 2.0]);
+        assert_eq!(coarse.links, vec![(0, 1)]);
+        assert_eq!(level.parents, vec![0, 0, 1, 1]);
+    }
+
+    #[test]
+    fn keeps_fixed_nodes_without_coarsening() {
+        let positions = Multilevel::new([(0, 1), (1, 2)]).layout([
+            Node::default().fixed_position(100.0, 100.0),
+            Node::default(),
+            Node::default(),
+        ]);
+
+        assert_eq!(positions[0], [100.0, 100.0]);
+    }
+
+    #[test]
+    fn keeps_fixed_nodes_in_the_original_graph() {
+        let (nodes, links) = fjadra_data::random_tree::generate(300);
+
+        let positions = Multilevel::new(links.iter().copied()).layout((0..nodes.len()).map(|i| {
+            if i == 0 {
+                Node::default().fixed_position(500.0, -500.0)
+            } else {
+                Node::default()
+            }
+        }));
+
+        assert_eq!(positions[0], [500.0, -500.0]);
+    }
+
+    #[test]
+    fn lays_out_large_trees() {
+        let (nodes, links) = fjadra_data::random_tree::generate(2_000);
+
+        let positions =
+            Multilevel::new(links.iter().copied()).layout(nodes.iter().map(|_| Node::default()));
+
+        assert_eq!(positions.len(), nodes.len());
+        assert!(positions.iter().flatten().all(|v| v.is_finite()));
+
+        // A plain simulation that starts from the phyllotaxis arrangement ends up with an
+        // average link length of about 140 for this tree.
+        let average = links
+            .iter()
+            .map(|&(s, t)| distance(positions[s], positions[t]))
+            .sum::<f64>()
+            / links.len() as f64;
+        assert!(average < 100.0, "average link length is {average}");
+    }
+}
@@ -0,0 +1,301 @@
+use crate::{force::initial_position, graph::Graph, lcg::Lcg, InitialLayout};
+
+/// Places nodes according to the low eigenvectors of the graph Laplacian.
+///
+/// We compute the degree-normalized eigenvectors as described in Koren, "Drawing Graphs by
+/// Eigenvectors: Theory and Practice" (2005), using power iteration on the random-walk matrix.
+/// The positions can be used as a starting point for a [`Simulation`](crate::Simulation), see
+/// [`Placement::layout`](crate::Placement::layout).
+#[derive(Clone, Debug)]
+pub struct Spectral {
+    links: Vec<(usize, usize)>,
+    iterations: usize,
+    tolerance: f64,
+}
+
+impl Spectral {
+    /// Creates the placement from the eigenvectors of the Laplacian of the graph that the links
+    /// describe.
+    pub fn new(links: impl IntoIterator<Item = (usize, usize)>) -> Self {
+        Self {
+            links: links.into_iter().collect(),
+            iterations: 1_000,
+            tolerance: 1e-7,
+        }
+    }
+
+    /// Sets the maximum number of power iterations per eigenvector.
+    pub fn iterations(mut self, iterations: usize) -> Self {
+        self.iterations = iterations;
+        self
+    }
+
+    /// Stops the power iteration once the eigenvector changes less than `tolerance`.
+    pub fn tolerance(mut self, tolerance: f64) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Computes the positions of `len` nodes.
+    ///
+    /// Each connected component is laid out on its own and scaled to cover a similar area as the
+    /// phyllotaxis arrangement that [`SimulationBuilder::build`](crate::SimulationBuilder::build)
+    /// uses by default. The components are then packed next to each other. Isolated nodes have no
+    /// spectral position, so they continue that arrangement around the others.
+    pub fn positions(&self, len: usize) -> Vec<[f64; 2]> {
+        let graph = Graph::from_links(len, self.links.iter().copied());
+        let (components, isolated): (Vec<_>, Vec<_>) = graph
+            .components()
+            .into_iter()
+            .partition(|component| component.len() > 1);
+
+        let mut local = vec![0; len];
+        for component in &components {
+            for (i, &node) in component.iter().enumerate() {
+                local[node] = i;
+            }
+        }
+
+        let mut random = Lcg::default();
+        let mut layouts = components
+            .iter()
+            .map(|component| self.component(&graph, component, &local, &mut random))
+            .collect::<Vec<_>>();
+        pack(&mut layouts);
+
+        let mut positions = vec![[0.0; 2]; len];
+        for (component, layout) in components.iter().zip(layouts) {
+            for (&node, position) in component.iter().zip(layout) {
+                positions[node] = position;
+            }
+        }
+        let connected = len - isolated.len();
+        for (k, component) in isolated.iter().enumerate() {
+            positions[component[0]] = initial_position(connected + k);
+        }
+        positions
+    }
+
+    /// Lays out a connected component, whose nodes are at the `local` indices of `nodes`.
+    fn component(
+        &self,
+        graph: &Graph,
+        nodes: &[usize],
+        local: &[usize],
+        random: &mut Lcg,
+    ) -> Vec<[f64; 2]> {
+        let n = nodes.len();
+        let degrees = nodes
+            .iter()
+            .map(|&node| graph.neighbors(node).len() as f64)
+            .collect::<Vec<_>>();
+
+        // The constant vector is the trivial eigenvector, so we start the deflation with it.
+        let mut eigenvectors = vec![vec![1.0; n]];
+        for _ in 0..2 {
+            let mut x = (0..n)
+                .map(|_| random.next().unwrap_or_default() - 0.5)
+                .collect::<Vec<_>>();
+            orthogonalize(&mut x, &eigenvectors, &degrees);
+            normalize(&mut x);
+
+            for _ in 0..self.iterations {
+                // Multiplies with `(I + D⁻¹A) / 2`, whose spectrum is shifted to `[0, 1]`.
+                let mut next = nodes
+                    .iter()
+                    .enumerate()
+                    .map(|(i, &node)| {
+                        let sum = graph
+                            .neighbors(node)
+                            .iter()
+                            .map(|&j| x[local[j]])
+                            .sum::<f64>();
+                        (x[i] + sum / degrees[i]) / 2.0
+                    })
+                    .collect::<Vec<_>>();
+                orthogonalize(&mut next, &eigenvectors, &degrees);
+                normalize(&mut next);
+
+                let change = x
+                    .iter()
+                    .zip(&next)
+                    .map(|(a, b)| (a - b).abs())
+                    .fold(0.0, f64::max);
+                x = next;
+                if change < self.tolerance {
+                    break;
+                }
+            }
+            eigenvectors.push(x);
+        }
+
+        // Matches the root mean square radius of the phyllotaxis arrangement.
+        let radius = 10.0 * (n as f64 / 2.0).sqrt();
+        let rms = (eigenvectors[1]
+            .iter()
+            .zip(&eigenvectors[2])
+            .map(|(x, y)| x * x + y * y)
+            .sum::<f64>()
+            / n as f64)
+            .sqrt();
+        let scale = if rms > 0.0 { radius / rms } else { 0.0 };
+
+        eigenvectors[1]
+            .iter()
+            .zip(&eigenvectors[2])
+            .map(|(x, y)| [x * scale, y * scale])
+            .collect()
+    }
+}
+
+impl InitialLayout for Spectral {
+    fn positions(&self, len: usize) -> Vec<[f64; 2]> {
+        Self::positions(self, len)
+    }
+}
+
+/// Packs the layouts of the components into rows, tallest first, and centers them at the origin.
+fn pack(layouts: &mut [Vec<[f64; 2]>]) {
+    // The space that is left between two components.
+    const GAP: f64 = 10.0;
+
+    let bounds = layouts
+        .iter()
+        .map(|points| {
+            points.iter().fold(
+                ([f64::INFINITY; 2], [f64::NEG_INFINITY; 2]),
+                |([x0, y0], [x1, y1]), &[x, y]| ([x0.min(x), y0.min(y)], [x1.max(x), y1.max(y)]),
+            )
+        })
+        .collect::<Vec<_>>();
+    let sizes = bounds
+        .iter()
+        .map(|([x0, y0], [x1, y1])| [x1 - x0 + GAP, y1 - y0 + GAP])
+        .collect::<Vec<_>>();
+
+    let area = sizes.iter().map(|[w, h]| w * h).sum::<f64>();
+    let widest = sizes.iter().map(|[w, _]| *w).fold(0.0, f64::max);
+    let row_width = area.sqrt().max(widest);
+
+    let mut order = (0..layouts.len()).collect::<Vec<_>>();
+    order.sort_by(|&a, &b| sizes[b][1].total_cmp(&sizes[a][1]));
+
+    let mut offsets = vec![[0.0; 2]; layouts.len()];
+    let ([mut x, mut y], mut row_height, mut width) = ([0.0, 0.0], 0.0, 0.0f64);
+    for i in order {
+        let [w, h] = sizes[i];
+        if x > 0.0 && x + w > row_width {
+            x = 0.0;
+            y += row_height;
+            row_height = 0.0;
+        }
+        let [x0, y0] = bounds[i].0;
+        offsets[i] = [x - x0, y - y0];
+        x += w;
+        row_height = f64::max(row_height, h);
+        width = width.max(x);
+    }
+
+    let center = [(width - GAP) / 2.0, (y + row_height - GAP) / 2.0];
+    for (points, [dx, dy]) in layouts.iter_mut().zip(offsets) {
+        for [x, y] in points {
+            *x += dx - center[0];
+            *y += dy - center[1];
+        }
+    }
+}
+
+/// Makes `x` orthogonal to the `basis` with respect to the inner product that is weighted by the degrees.
+fn orthogonalize(x: &mut [f64], basis: &[Vec<f64>], degrees: &[f64]) {
+    for u in basis {
+        let (mut xu, mut uu) = (0.0, 0.0);
+        for ((xi, ui), di) in x.iter().zip(u).zip(degrees) {
+            xu += xi * ui * di;
+            uu += ui * ui * di;
+        }
+        if uu > 0.0 {
+            let f = xu / uu;
+            for (xi, ui) in x.iter_mut().zip(u) {
+                *xi -= f * ui;
+            }
+        }
+    }
+}
+
+fn normalize(x: &mut [f64]) {
+    let norm = x.iter().map(|v| v * v).sum::<f64>().sqrt();
+    if norm > 0.0 {
+        for v in x {
+            *v /= norm;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{layout::distance, Node, Placement, SimulationBuilder};
+
+    use super::*;
+
+    #[test]
+    fn orders_the_nodes_of_a_path() {
+        let positions = Spectral::new((0..9).map(|i| (i, i + 1))).positions(10);
+
+        let xs = positions.iter().map(|[x, _]| *x).collect::<Vec<_>>();
+        let increasing = xs.windows(2).all(|w| w[0] < w[1]);
+        let decreasing = xs.windows(2).all(|w| w[0] > w[1]);
+        assert!(increasing || decreasing, "{xs:?}");
+    }
+
+    #[test]
+    fn places_isolated_nodes_around_the_others() {
+        let positions = Spectral::new((0..9).map(|i| (i, i + 1))).positions(11);
+
+        let xs = positions[..10].iter().map(|[x, _]| *x).collect::<Vec<_>>();
+        let increasing = xs.windows(2).all(|w| w[0] < w[1]);
+        let decreasing = xs.windows(2).all(|w| w[0] > w[1]);
+        assert!(increasing || decreasing, "{xs:?}");
+        assert!(xs[0].abs() > 1.0 && xs[9].abs() > 1.0, "{xs:?}");
+        assert_eq!(positions[10], initial_position(10));
+    }
+
+    #[test]
+    fn spreads_out_every_component() {
+        let links = [(0, 1), (1, 2), (3, 4), (4, 5), (6, 7), (7, 8)];
+        let positions = Spectral::new(links).positions(9);
+
+        for (i, &a) in positions.iter().enumerate() {
+            for &b in &positions[i + 1..] {
+                assert!(distance(a, b) > 1.0, "{positions:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn initializes_simulations() {
+        let links = [(0, 1), (1, 2), (2, 3)];
+        let simulation = SimulationBuilder::default()
+            .with_placement(Placement::layout(Spectral::new(links)))
+            .build([
+                Node::default(),
+                Node::default().position(42.0, 42.0),
+                Node::default(),
+                Node::default(),
+            ]);
+
+        let expected = Spectral::new(links).positions(4);
+        let positions = simulation.positions().collect::<Vec<_>>();
+        assert_eq!(positions[0], expected[0]);
+        assert_eq!(positions[1], [42.0, 42.0]);
+        assert_eq!(positions[3], expected[3]);
+    }
+
+    #[test]
+    fn handles_empty_graphs() {
+        assert!(Spectral::new([]).positions(0).is_empty());
+        assert_eq!(
+            Spectral::new([]).positions(3),
+            (0..3).map(initial_position).collect::<Vec<_>>()
+        );
+    }
+}
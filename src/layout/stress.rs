@@ -0,0 +1,198 @@
+use crate::force::Node;
+
+use crate::graph::Graph;
+
+use super::initial_particles;
+
+/// Lays out a graph by minimizing its stress, i.e. the weighted difference between the
+/// Euclidean distances of the nodes and their shortest-path distances in the graph.
+///
+/// We use the localized majorization update from Gansner et al., "Graph Drawing by Stress
+/// Majorization" (2004), with the usual weights of `1 / d²`. Pairs of nodes that are not
+/// connected by a path do not contribute to the stress.
+#[derive(Clone, Debug)]
+pub struct StressMajorization {
+    links: Vec<(usize, usize)>,
+    edge_length: f64,
+    iterations: usize,
+    tolerance: f64,
+    warm_start: Option<Vec<[f64; 2]>>,
+}
+
+impl StressMajorization {
+    /// Creates the layout for the given links.
+    ///
+    /// The target distance of two nodes is the number of hops between them times the edge length.
+    pub fn new(links: impl IntoIterator<Item = (usize, usize)>) -> Self {
+        Self {
+            links: links.into_iter().collect(),
+            edge_length: 30.0,
+            iterations: 300,
+            tolerance: 1e-4,
+            warm_start: None,
+        }
+    }
+
+    /// Sets the desired length of a single edge.
+    pub fn edge_length(mut self, edge_length: f64) -> Self {
+        self.edge_length = edge_length;
+        self
+    }
+
+    /// Sets the maximum number of iterations.
+    pub fn iterations(mut self, iterations: usize) -> Self {
+        self.iterations = iterations;
+        self
+    }
+
+    /// Stops the optimization once the relative improvement of the stress falls below `tolerance`.
+    pub fn tolerance(mut self, tolerance: f64) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Starts the optimization from existing positions, for example from
+    /// [`Simulation::positions`](crate::Simulation::positions).
+    pub fn warm_start(mut self, positions: impl IntoIterator<Item = [f64; 2]>) -> Self {
+        self.warm_start = Some(positions.into_iter().collect());
+        self
+    }
+
+    /// Computes the positions of the nodes, in the order in which they were given.
+    ///
+    /// Nodes that were created with [`Node::fixed_position`] are not moved.
+    pub fn layout<N>(&self, nodes: impl IntoIterator<Item = N>) -> Vec<[f64; 2]>
+    where
+        N: Into<Node>,
+    {
+        let particles = initial_particles(nodes, self.warm_start.as_deref());
+        let graph = Graph::from_links(particles.len(), self.links.iter().copied());
+        let distances = graph
+            .distances()
+            .into_iter()
+            .map(|row| row.into_iter().map(|d| d * self.edge_length).collect())
+            .collect::<Vec<Vec<_>>>();
+
+        let fixed = particles
+            .iter()
+            .map(|p| p.fx.is_some() || p.fy.is_some())
+            .collect::<Vec<_>>();
+        let mut positions = particles.iter().map(|p| [p.x, p.y]).collect::<Vec<_>>();
+
+        let mut current = stress(&positions, &distances);
+        for _ in 0..self.iterations {
+            for i in 0..positions.len() {
+                if fixed[i] {
+                    continue;
+                }
+
+                let [xi, yi] = positions[i];
+                let (mut x, mut y, mut weights) = (0.0, 0.0, 0.0);
+                for (j, &d) in distances[i].iter().enumerate() {
+                    if i == j || !d.is_finite() {
+                        continue;
+                    }
+
+                    let w = 1.0 / (d * d);
+                    let [xj, yj] = positions[j];
+                    let l = (xi - xj).hypot(yi - yj);
+                    x += w * xj;
+                    y += w * yj;
+                    if l > 0.0 {
+                        x += w * d * (xi - xj) / l;
+                        y += w * d * (yi - yj) / l;
+                    }
+                    weights += w;
+                }
+
+                if weights > 0.0 {
+                    positions[i] = [x / weights, y / weights];
+                }
+            }
+
+            let next = stress(&positions, &distances);
+            let converged = current == 0.0 || (current - next) / current < self.tolerance;
+            current = next;
+            if converged {
+                break;
+            }
+        }
+
+        positions
+    }
+}
+
+/// Computes the weighted stress of a layout.
+fn stress(positions: &[[f64; 2]], distances: &[Vec<f64>]) -> f64 {
+    let mut stress = 0.0;
+    for (i, row) in distances.iter().enumerate() {
+        for (j, &d) in row.iter().enumerate().skip(i + 1) {
+            if d.is_finite() && d > 0.0 {
+                let [xi, yi] = positions[i];
+                let [xj, yj] = positions[j];
+                let diff = (xi - xj).hypot(yi - yj) - d;
+                stress += diff * diff / (d * d);
+            }
+        }
+    }
+    stress
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{layout::distance, Link, ManyBody, SimulationBuilder};
+
+    use super::*;
+
+    #[test]
+    fn preserves_graph_distances_of_a_path() {
+        let links = [(0, 1), (1, 2), (2, 3)];
+        let positions = StressMajorization::new(links)
+            .tolerance(1e-9)
+            .layout((0..4).map(|_| Node::default()));
+
+        approx::assert_abs_diff_eq!(distance(positions[0], positions[1]), 30.0, epsilon = 0.1);
+        approx::assert_abs_diff_eq!(distance(positions[0], positions[3]), 90.0, epsilon = 0.5);
+    }
+
+    #[test]
+    fn respects_fixed_positions() {
+        let positions = StressMajorization::new([(0, 1)]).layout([
+            Node::default().fixed_position(100.0, 100.0),
+            Node::default(),
+        ]);
+
+        assert_eq!(positions[0], [100.0, 100.0]);
+        approx::assert_abs_diff_eq!(distance(positions[0], positions[1]), 30.0, epsilon = 0.1);
+    }
+
+    #[test]
+    fn improves_simulation_results() {
+        let links = [(0, 1), (1, 2), (2, 3), (3, 4), (4, 0), (0, 2)];
+        let nodes = || (0..5).map(|_| Node::default());
+
+        let mut simulation = SimulationBuilder::default()
+            .build(nodes())
+            .add_force("link", Link::new(links))
+            .add_force("charge", ManyBody::new());
+        simulation.step();
+
+        let distances = Graph::from_links(5, links)
+            .distances()
+            .into_iter()
+            .map(|row| row.into_iter().map(|d| d * 30.0).collect())
+            .collect::<Vec<Vec<_>>>();
+
+        let before = simulation.positions().collect::<Vec<_>>();
+        let after = StressMajorization::new(links)
+            .warm_start(simulation.positions())
+            .layout(nodes());
+
+        assert_eq!(after.len(), before.len());
+        let (before, after) = (stress(&before, &distances), stress(&after, &distances));
+        assert!(
+            after < 0.97 * before,
+            "stress went from {before} to {after}"
+        );
+    }
+}
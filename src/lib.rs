@@ -1,9 +1,12 @@
 #![doc = include_str!("../README.md")]
 
+pub mod bundling;
 pub mod extent;
 pub mod force;
+pub mod layout;
 pub mod quadtree;
 
+pub(crate) mod graph;
 pub(crate) mod lcg;
 
 pub use force::*;
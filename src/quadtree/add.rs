@@ -1,79 +1,75 @@
-use crate::quadtree::create_empty_internal;
-
-use super::{create_leaf, indexer::Indexer, Node, Quadtree};
+use super::{indexer::Indexer, Node, Quadtree};
 
 impl<Q: Default, T> Quadtree<Q, T> {
+    /// Inserts a value at `(x, y)`.
+    ///
+    /// Values that can't be covered, see [`Quadtree::cover`], are not inserted. A value that can't
+    /// be told apart from an existing point at the precision of the extent is added to the leaf of
+    /// that point, and is reported at the position of the leaf from then on.
     pub fn insert(&mut self, x: f64, y: f64, value: T) {
-        self.cover(x, y);
-
-        let node = self.root.as_mut();
+        if !self.try_cover(x, y) {
+            return;
+        }
 
-        let Some(mut node) = node else {
-            self.root = Some(create_leaf(&mut self.store, x, y, value).into());
+        let Some(mut id) = self.root else {
+            self.root = Some(self.arena.insert(Node::leaf(x, y, value)));
             return;
         };
 
-        // TODO(grtlr): confirm correctness of this conversion w.r.t. floating-point
-        // precision.
-        let mut ix = Indexer::with_extent(
-            [self.x0 as f64, self.y0 as f64],
-            [self.x1 as f64, self.y1 as f64],
-        );
+        let mut ix = Indexer::with_extent([self.x0, self.y0], [self.x1, self.y1]);
 
         '_descend: loop {
-            match node.as_mut() {
-                Node::Internal {
-                    ref mut children, ..
-                } => {
+            match self.arena.get_mut(id) {
+                Node::Internal { children } => {
                     let i = ix.get_and_descend(x, y);
-                    if let Some(ref mut n) = children[i] {
-                        node = n;
+                    if let Some(child) = children[i] {
+                        id = child;
                     } else {
-                        children[i] = Some(create_leaf(&mut self.store, x, y, value).into());
+                        let leaf = self.arena.insert(Node::leaf(x, y, value));
+                        self.arena.children_mut(id)[i] = Some(leaf);
                         return;
                     }
                 }
                 // The new point coincides with the existing point.
-                Node::Leaf {
-                    ref mut data,
-                    x: xp,
-                    y: yp,
-                    ..
-                } if x == *xp && y == *yp => {
-                    data.insert(value);
+                Node::Leaf { data, x: xp, y: yp } if x == *xp && y == *yp => {
+                    data.push(value);
                     return;
                 }
-                old_leaf @ Node::Leaf { .. } => {
-                    let inner = std::mem::replace(old_leaf, create_empty_internal(&mut self.store));
-                    if let Node::Leaf { x: xp, y: yp, .. } = inner {
-                        let mut new_internal = old_leaf;
+                Node::Leaf { x: xp, y: yp, data } => {
+                    let (xp, yp) = (*xp, *yp);
 
-                        loop {
-                            let Node::Internal {
-                                children: ref mut parent,
-                                ..
-                            } = new_internal
-                            else {
-                                unreachable!()
-                            };
+                    // Points that can't be told apart at the precision of the extent share a
+                    // leaf, like in `add_all`.
+                    if ix.clone().merges([xp, yp], [x, y]) {
+                        data.push(value);
+                        return;
+                    }
 
-                            let j = ix.get(xp, yp);
-                            let i = ix.get_and_descend(x, y);
+                    // The existing leaf moves to a new slot, and its slot becomes the internal
+                    // node that splits the two points.
+                    let old_leaf = std::mem::replace(self.arena.get_mut(id), Node::empty());
+                    let old_leaf = self.arena.insert(old_leaf);
 
-                            debug_assert!(i < 4);
-                            debug_assert!(j < 4);
+                    let mut parent = id;
+                    loop {
+                        let j = ix.get(xp, yp);
+                        let i = ix.get_and_descend(x, y);
 
-                            if i != j {
-                                parent[i] = Some(create_leaf(&mut self.store, x, y, value).into());
-                                parent[j] = Some(inner.into());
-                                return;
-                            }
+                        debug_assert!(i < 4);
+                        debug_assert!(j < 4);
 
-                            new_internal =
-                                parent[i].insert(create_empty_internal(&mut self.store).into());
+                        if i != j {
+                            let leaf = self.arena.insert(Node::leaf(x, y, value));
+                            let children = self.arena.children_mut(parent);
+                            children[i] = Some(leaf);
+                            children[j] = Some(old_leaf);
+                            return;
                         }
+
+                        let internal = self.arena.insert(Node::empty());
+                        self.arena.children_mut(parent)[i] = Some(internal);
+                        parent = internal;
                     }
-                    unreachable!()
                 }
             }
         }
@@ -104,7 +100,7 @@ mod test {
 
         q.insert_default(0.9, 0.9);
         assert!(matches!(
-            q.root().unwrap().children().unwrap(),
+            q.children_at(&[]).unwrap(),
             [
                 Some(&Node::Leaf { x: 0., y: 0., .. }),
                 None,
@@ -115,7 +111,7 @@ mod test {
 
         q.insert_default(0.9, 0.0);
         assert!(matches!(
-            q.root().unwrap().children().unwrap(),
+            q.children_at(&[]).unwrap(),
             [
                 Some(&Node::Leaf { x: 0., y: 0., .. }),
                 Some(&Node::Leaf { x: 0.9, y: 0., .. }),
@@ -126,7 +122,7 @@ mod test {
 
         q.insert_default(0., 0.9);
         assert!(matches!(
-            q.root().unwrap().children().unwrap(),
+            q.children_at(&[]).unwrap(),
             [
                 Some(&Node::Leaf { x: 0., y: 0., .. }),
                 Some(&Node::Leaf { x: 0.9, y: 0., .. }),
@@ -137,7 +133,7 @@ mod test {
 
         q.insert_default(0.4, 0.4);
         assert!(matches!(
-            q.root().unwrap().children().unwrap(),
+            q.children_at(&[]).unwrap(),
             [
                 Some(&Node::Internal { .. }),
                 Some(&Node::Leaf { x: 0.9, y: 0., .. }),
@@ -146,7 +142,7 @@ mod test {
             ]
         ));
         assert!(matches!(
-            q.root().unwrap().children().unwrap()[0].unwrap().children(),
+            q.children_at(&[0]),
             Some([
                 Some(&Node::Leaf { x: 0., y: 0., .. }),
                 None,
@@ -164,7 +160,7 @@ mod test {
 
         q.insert_default(1., 1.);
         assert!(matches!(
-            q.root().unwrap().children().unwrap(),
+            q.children_at(&[]).unwrap(),
             [
                 Some(&Node::Leaf { x: 0., y: 0., .. }),
                 None,
@@ -175,7 +171,7 @@ mod test {
 
         q.insert_default(1., 0.);
         assert!(matches!(
-            q.root().unwrap().children().unwrap(),
+            q.children_at(&[]).unwrap(),
             [
                 Some(&Node::Leaf { x: 0., y: 0., .. }),
                 Some(&Node::Leaf { x: 1., y: 0., .. }),
@@ -186,7 +182,7 @@ mod test {
 
         q.insert_default(0., 1.);
         assert!(matches!(
-            q.root().unwrap().children().unwrap(),
+            q.children_at(&[]).unwrap(),
             [
                 Some(&Node::Leaf { x: 0., y: 0., .. }),
                 Some(&Node::Leaf { x: 1., y: 0., .. }),
@@ -200,7 +196,7 @@ mod test {
     fn handles_points_being_to_the_left_of_quadtree_bounds() {
         let mut q = Quadtree::with_extent([0., 0.], [2., 2.]);
         q.insert_default(-1., 1.);
-        assert_eq!(q.extent(), ([-4, 0], [4, 8]));
+        assert_eq!(q.extent(), ([-4., 0.], [4., 8.]));
     }
 
     #[test]
@@ -214,7 +210,7 @@ mod test {
 
         q.insert_default(1., 0.);
         assert!(matches!(
-            q.root().unwrap().children().unwrap(),
+            q.children_at(&[]).unwrap(),
             [
                 Some(&Node::Leaf { x: 0., y: 0., .. }),
                 Some(&Node::Leaf { x: 1., y: 0., .. }),
@@ -225,7 +221,7 @@ mod test {
 
         q.insert_default(0., 1.);
         assert!(matches!(
-            q.root().unwrap().children().unwrap(),
+            q.children_at(&[]).unwrap(),
             [
                 Some(&Node::Leaf { x: 0., y: 0., .. }),
                 Some(&Node::Leaf { x: 1., y: 0., .. }),
@@ -236,7 +232,7 @@ mod test {
 
         q.insert_default(0., 1.);
         assert!(matches!(
-            q.root().unwrap().children().unwrap(),
+            q.children_at(&[]).unwrap(),
             [
                 Some(&Node::Leaf { x: 0., y: 0., .. }),
                 Some(&Node::Leaf { x: 1., y: 0., .. }),
@@ -245,9 +241,9 @@ mod test {
             ]
         ));
         assert_eq!(
-            q.root().unwrap().children().unwrap()[2]
+            q.children_at(&[]).unwrap()[2]
                 .unwrap()
-                .leaf()
+                .bucket()
                 .unwrap()
                 .iter()
                 .collect::<Vec<_>>(),
@@ -259,7 +255,7 @@ mod test {
     fn trivial_bounds_for_first_point() {
         let mut q = Quadtree::default();
         q.insert_default(1.0, 2.0);
-        assert_eq!(q.extent(), ([1, 2], [2, 3]));
+        assert_eq!(q.extent(), ([1., 2.], [2., 3.]));
         assert!(matches!(
             q.root().unwrap(),
             Node::Leaf { x: 1.0, y: 2.0, .. }
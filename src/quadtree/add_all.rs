@@ -1,11 +1,216 @@
-use super::Quadtree;
+use super::{arena::Arena, arena::NodeId, LeafBucket, Node, Quadtree};
+
+/// Builds a tree top-down, by partitioning the points into the quadrants of the extent.
+///
+/// Points that can't be told apart, because the extent can't be split any further at the
+/// precision of `f64`, share a single leaf at the position of the first one, like in `insert`.
+fn build<Q: Default, T>(
+    arena: &mut Arena<Q, T>,
+    points: Vec<(f64, f64, T)>,
+    extent: [f64; 4],
+) -> Option<NodeId> {
+    let mut points = points
+        .into_iter()
+        .map(|(x, y, value)| (x, y, Some(value)))
+        .collect::<Vec<_>>();
+
+    // The points of a subtree are a contiguous range in `order`, which is partitioned in place.
+    // Partitioning is stable, so coincident points keep the order in which they were given.
+    let mut order = (0..points.len()).collect::<Vec<_>>();
+    let mut scratch = vec![0; points.len()];
+
+    let mut root = None;
+    let mut stack = vec![(None, 0..points.len(), extent)];
+    while let Some((slot, range, [x0, y0, x1, y1])) = stack.pop() {
+        let ids = &mut order[range.clone()];
+        let (x, y, _) = points[ids[0]];
+        let xm = (x0 + x1) / 2.0;
+        let ym = (y0 + y1) / 2.0;
+
+        let coincident = ids.iter().all(|&i| points[i].0 == x && points[i].1 == y);
+        let splittable = (x0 < xm && xm < x1) || (y0 < ym && ym < y1);
+
+        let id = if coincident || !splittable {
+            let mut values = ids.iter().map(|&i| {
+                points[i]
+                    .2
+                    .take()
+                    .expect("every point should be in one leaf")
+            });
+            let data = LeafBucket {
+                first: values.next().expect("ranges should not be empty"),
+                others: values.collect(),
+            };
+            arena.insert(Node::Leaf { x, y, data })
+        } else {
+            let quadrant =
+                |i: usize| (usize::from(points[i].1 >= ym) << 1) | usize::from(points[i].0 >= xm);
+
+            let mut counts = [0; 4];
+            for &i in ids.iter() {
+                counts[quadrant(i)] += 1;
+            }
+            let starts = [
+                0,
+                counts[0],
+                counts[0] + counts[1],
+                counts[0] + counts[1] + counts[2],
+            ];
+
+            let mut next = starts;
+            let buffer = &mut scratch[..ids.len()];
+            for &i in ids.iter() {
+                let q = quadrant(i);
+                buffer[next[q]] = i;
+                next[q] += 1;
+            }
+            ids.copy_from_slice(buffer);
+
+            let id = arena.insert(Node::empty());
+            let extents = [
+                [x0, y0, xm, ym],
+                [xm, y0, x1, ym],
+                [x0, ym, xm, y1],
+                [xm, ym, x1, y1],
+            ];
+            for q in 0..4 {
+                if counts[q] > 0 {
+                    let start = range.start + starts[q];
+                    stack.push((Some((id, q)), start..start + counts[q], extents[q]));
+                }
+            }
+            id
+        };
+
+        match slot {
+            Some((parent, q)) => arena.children_mut(parent)[q] = Some(id),
+            None => root = Some(id),
+        }
+    }
+    root
+}
 
 impl<Q: Default, T> Quadtree<Q, T> {
+    /// Inserts all values, computing the extent that covers them only once.
+    ///
+    /// If the tree is empty, it is built top-down. Like in d3, values with NaN coordinates are
+    /// skipped. Values that can't be covered are not inserted, see [`Quadtree::insert`].
     pub fn add_all(&mut self, values: impl IntoIterator<Item = (f64, f64, T)>) {
-        // TODO(grtlr): Improve the performance by computing the extent only
-        // once. Similar to how it's done in d3.
-        for value in values {
-            self.insert(value.0, value.1, value.2);
+        let mut points = Vec::new();
+        let mut min = [f64::INFINITY; 2];
+        let mut max = [f64::NEG_INFINITY; 2];
+        for (x, y, value) in values {
+            if !x.is_finite() || !y.is_finite() {
+                continue;
+            }
+
+            min = [min[0].min(x), min[1].min(y)];
+            max = [max[0].max(x), max[1].max(y)];
+            points.push((x, y, value));
+        }
+
+        if points.is_empty() {
+            return;
         }
+
+        // If the bounds can't be covered, we find out which points can be covered one by one.
+        let covered = self.try_cover(min[0], min[1]) && self.try_cover(max[0], max[1]);
+
+        if self.root.is_some() || !covered {
+            for (x, y, value) in points {
+                self.insert(x, y, value);
+            }
+        } else {
+            let extent = [self.x0, self.y0, self.x1, self.y1];
+            self.root = build(&mut self.arena, points, extent);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::quadtree::Visit;
+
+    type Quadtree = super::Quadtree<(), usize>;
+
+    fn points() -> Vec<(f64, f64, usize)> {
+        (0..500)
+            .map(|i| {
+                let i_f = i as f64;
+                ((i_f * 7.3).sin() * 100.0, (i_f * 3.1).cos() * 50.0, i)
+            })
+            .chain([(0.5, 0.5, 500), (0.5, 0.5, 501), (0.5, 0.5, 502)])
+            .collect()
+    }
+
+    fn extents(q: &mut Quadtree) -> Vec<[f64; 4]> {
+        let mut extents = Vec::new();
+        q.visit(|quad| {
+            extents.push(quad.extent().into());
+            Visit::Continue
+        });
+        extents
+    }
+
+    #[test]
+    fn builds_the_same_tree_as_sequential_insertion() {
+        let mut bulk = Quadtree::default();
+        bulk.add_all(points());
+
+        // Covering the bounds first results in the same extent as in the bulk loader.
+        let mut sequential = Quadtree::default();
+        sequential.cover(-100.0, -50.0);
+        sequential.cover(100.0, 50.0);
+        for (x, y, i) in points() {
+            sequential.insert(x, y, i);
+        }
+
+        assert_eq!(bulk.extent(), sequential.extent());
+        assert_eq!(extents(&mut bulk), extents(&mut sequential));
+        assert_eq!(
+            bulk.data().collect::<Vec<_>>(),
+            sequential.data().collect::<Vec<_>>()
+        );
+        assert_eq!(bulk.size(), 503);
+    }
+
+    #[test]
+    fn adds_to_existing_trees() {
+        let mut q = Quadtree::default();
+        q.add_all(points().into_iter().take(100));
+        q.add_all(points().into_iter().skip(100));
+
+        let mut expected = points()
+            .iter()
+            .map(|&(x, y, i)| (x.to_bits(), y.to_bits(), i))
+            .collect::<Vec<_>>();
+        let mut actual = q
+            .data()
+            .map(|(x, y, &i)| (x.to_bits(), y.to_bits(), i))
+            .collect::<Vec<_>>();
+        expected.sort_unstable_by_key(|p| p.2);
+        actual.sort_unstable_by_key(|p| p.2);
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn skips_non_finite_values() {
+        let mut q = Quadtree::default();
+        q.add_all([(f64::INFINITY, 0.0, 0), (1.0, 1.0, 1), (f64::NAN, 0.0, 2)]);
+        assert_eq!(q.size(), 1);
+        assert_eq!(q.extent(), ([1.0, 1.0], [2.0, 2.0]));
+    }
+
+    #[test]
+    fn skips_values_whose_extent_would_overflow() {
+        let mut q = Quadtree::default();
+        q.add_all([
+            (0.0, 0.0, 0),
+            (1e308, 0.0, 1),
+            (1.7e308, 0.0, 2),
+            (3.0, 2.0, 3),
+        ]);
+        assert_eq!(q.extent(), ([0.0, 0.0], [4.0, 4.0]));
+        assert_eq!(q.data().map(|(.., &i)| i).collect::<Vec<_>>(), [0, 3]);
     }
 }
@@ -0,0 +1,97 @@
+use super::{Node, Quadtree};
+
+/// Computes the values of the quads bottom-up, as used for Barnes–Hut style approximations.
+pub trait Aggregator<Q, T> {
+    /// Computes the value of a leaf from its points, which all lie at `(x, y)`.
+    fn leaf<'a>(&self, x: f64, y: f64, points: impl Iterator<Item = &'a T>) -> Q
+    where
+        T: 'a;
+
+    /// Combines the values of the children of an internal node.
+    fn combine(&self, children: [Option<&Q>; 4]) -> Q;
+}
+
+impl<Q: Default, T> Quadtree<Q, T> {
+    /// Computes the value of every quad, children before their parents.
+    pub fn aggregate(&mut self, aggregator: &impl Aggregator<Q, T>) {
+        let Some(root) = self.root else {
+            return;
+        };
+
+        let mut stack = vec![root];
+        let mut order = Vec::new();
+        while let Some(id) = stack.pop() {
+            if let Node::Internal { children } = self.arena.get(id) {
+                stack.extend(children.iter().flatten());
+            }
+            order.push(id);
+        }
+
+        let values = &mut self.arena.values;
+        for id in order.into_iter().rev() {
+            values[id] = match self.arena.nodes[id].as_ref() {
+                Some(Node::Leaf { x, y, data }) => aggregator.leaf(*x, *y, data.iter()),
+                Some(Node::Internal { children }) => {
+                    aggregator.combine(children.map(|c| c.map(|c| &values[c])))
+                }
+                None => unreachable!("the tree should only point to nodes that were not removed"),
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Computes the number of points and their bounding box.
+    struct Bounds;
+
+    impl Aggregator<(usize, [f64; 4]), ()> for Bounds {
+        fn leaf<'a>(
+            &self,
+            x: f64,
+            y: f64,
+            points: impl Iterator<Item = &'a ()>,
+        ) -> (usize, [f64; 4]) {
+            (points.count(), [x, y, x, y])
+        }
+
+        fn combine(&self, children: [Option<&(usize, [f64; 4])>; 4]) -> (usize, [f64; 4]) {
+            children.into_iter().flatten().fold(
+                (
+                    0,
+                    [
+                        f64::INFINITY,
+                        f64::INFINITY,
+                        f64::NEG_INFINITY,
+                        f64::NEG_INFINITY,
+                    ],
+                ),
+                |(n, [x0, y0, x1, y1]), &(m, [a0, b0, a1, b1])| {
+                    (n + m, [x0.min(a0), y0.min(b0), x1.max(a1), y1.max(b1)])
+                },
+            )
+        }
+    }
+
+    #[test]
+    fn aggregates_bottom_up() {
+        let mut q = Quadtree::<(usize, [f64; 4])>::default();
+        q.add_all([
+            (1.0, 2.0, ()),
+            (3.0, 7.0, ()),
+            (3.0, 7.0, ()),
+            (6.0, 1.0, ()),
+            (1.5, 2.5, ()),
+        ]);
+        q.aggregate(&Bounds);
+
+        let mut root = None;
+        q.visit(|quad| {
+            root.get_or_insert(*quad.value());
+            crate::quadtree::Visit::Skip
+        });
+        assert_eq!(root, Some((5, [1.0, 1.0, 6.0, 7.0])));
+    }
+}
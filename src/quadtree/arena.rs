@@ -0,0 +1,116 @@
+use super::Node;
+
+/// Identifies a node in the [`Arena`] of a [`Quadtree`](super::Quadtree).
+pub(super) type NodeId = usize;
+
+/// Stores the nodes of a tree in a flat `Vec`, with the values of the quads in a parallel `Vec`.
+///
+/// The slots of removed nodes are reused by later insertions.
+#[derive(Clone, Debug)]
+pub(super) struct Arena<Q, T> {
+    pub(super) nodes: Vec<Option<Node<T>>>,
+    pub(super) values: Vec<Q>,
+    free: Vec<NodeId>,
+}
+
+impl<Q, T> Default for Arena<Q, T> {
+    fn default() -> Self {
+        Self {
+            nodes: Vec::new(),
+            values: Vec::new(),
+            free: Vec::new(),
+        }
+    }
+}
+
+impl<Q: Default, T> Arena<Q, T> {
+    pub fn insert(&mut self, node: Node<T>) -> NodeId {
+        if let Some(id) = self.free.pop() {
+            self.nodes[id] = Some(node);
+            self.values[id] = Q::default();
+            return id;
+        }
+
+        self.nodes.push(Some(node));
+        self.values.push(Q::default());
+        self.nodes.len() - 1
+    }
+}
+
+impl<Q, T> Arena<Q, T> {
+    /// Removes the node, so that its slot can be reused.
+    pub fn remove(&mut self, id: NodeId) -> Option<Node<T>> {
+        let node = self.nodes[id].take();
+        if node.is_some() {
+            self.free.push(id);
+        }
+        node
+    }
+
+    /// Removes all nodes, but keeps the allocated memory.
+    pub fn clear(&mut self) {
+        self.nodes.clear();
+        self.values.clear();
+        self.free.clear();
+    }
+
+    pub fn get(&self, id: NodeId) -> &Node<T> {
+        self.nodes[id]
+            .as_ref()
+            .expect("id should point to a node that was not removed")
+    }
+
+    pub fn get_mut(&mut self, id: NodeId) -> &mut Node<T> {
+        self.nodes[id]
+            .as_mut()
+            .expect("id should point to a node that was not removed")
+    }
+
+    /// Returns the children of an internal node.
+    pub fn children_mut(&mut self, id: NodeId) -> &mut [Option<NodeId>; 4] {
+        match self.get_mut(id) {
+            Node::Internal { children } => children,
+            Node::Leaf { .. } => unreachable!("expected an internal node"),
+        }
+    }
+
+    pub fn value(&self, id: NodeId) -> &Q {
+        &self.values[id]
+    }
+
+    #[cfg(test)]
+    pub fn len(&self) -> usize {
+        self.nodes.len() - self.free.len()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    type Arena = super::Arena<usize, ()>;
+
+    #[test]
+    fn insert_and_get() {
+        let mut arena = Arena::default();
+        let id = arena.insert(Node::empty());
+        assert!(matches!(arena.get(id), Node::Internal { .. }));
+        assert_eq!(*arena.value(id), 0);
+    }
+
+    #[test]
+    fn reuses_removed_slots() {
+        let mut arena = Arena::default();
+        let a = arena.insert(Node::empty());
+        let b = arena.insert(Node::leaf(0.0, 0.0, ()));
+        arena.values[a] = 42;
+        assert!(arena.remove(a).is_some());
+        assert!(arena.remove(a).is_none());
+        assert_eq!(arena.len(), 1);
+
+        let c = arena.insert(Node::empty());
+        assert_eq!(c, a);
+        assert_eq!(*arena.value(c), 0);
+        assert!(matches!(arena.get(b), Node::Leaf { .. }));
+    }
+}
@@ -0,0 +1,70 @@
+use super::{
+    arena::{Arena, NodeId},
+    Node, Quadtree,
+};
+
+fn copy_node<Q: Default + Clone, T: Clone>(
+    id: NodeId,
+    from: &Arena<Q, T>,
+    to: &mut Arena<Q, T>,
+) -> NodeId {
+    let copy = match from.get(id) {
+        Node::Leaf { .. } => to.insert(from.get(id).clone()),
+        Node::Internal { children } => {
+            let copy = to.insert(Node::empty());
+            for (i, child) in children.iter().enumerate() {
+                let child = child.map(|child| copy_node(child, from, to));
+                to.children_mut(copy)[i] = child;
+            }
+            copy
+        }
+    };
+    to.values[copy] = from.value(id).clone();
+    copy
+}
+
+impl<Q: Default + Clone, T: Clone> Quadtree<Q, T> {
+    /// Returns a copy of the tree, including the values of the quads.
+    ///
+    /// In contrast to [`Clone`], the copy only stores the quads that are still part of the tree, for example after points were removed.
+    pub fn copy(&self) -> Self {
+        let mut arena = Arena::default();
+        let root = self
+            .root
+            .map(|root| copy_node(root, &self.arena, &mut arena));
+
+        Self {
+            x0: self.x0,
+            y0: self.y0,
+            x1: self.x1,
+            y1: self.y1,
+            root,
+            arena,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    type Quadtree = super::Quadtree<usize, usize>;
+
+    #[test]
+    fn copies_a_compacted_tree() {
+        let points = (0..20)
+            .map(|i| ((i % 5) as f64, (i / 5) as f64, i))
+            .collect::<Vec<_>>();
+        let mut q = Quadtree::default();
+        q.add_all(points.iter().copied());
+        q.remove_all(points.iter().copied().skip(5));
+        q.visit_after(|mut quad| *quad.value_mut() = 42);
+
+        let copy = q.copy();
+        assert_eq!(copy.extent(), q.extent());
+        assert_eq!(
+            copy.data().collect::<Vec<_>>(),
+            q.data().collect::<Vec<_>>()
+        );
+        assert_eq!(copy.arena.len(), q.arena.len());
+        assert!(copy.arena.values.iter().all(|v| *v == 42));
+    }
+}
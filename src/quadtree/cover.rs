@@ -1,45 +1,49 @@
 use super::{Node, Quadtree};
 
 impl<Q: Default, T> Quadtree<Q, T> {
+    /// Expands the extent of the tree to cover `(x, y)`.
+    ///
+    /// Points can't be covered, and are ignored, if the extent that covers them would overflow,
+    /// i.e. if its width or its center would not be finite. This includes infinite coordinates.
     pub fn cover(&mut self, x: f64, y: f64) {
+        self.try_cover(x, y);
+    }
+
+    /// Expands the extent like [`Quadtree::cover`], and returns whether `(x, y)` could be covered.
+    pub(super) fn try_cover(&mut self, x: f64, y: f64) -> bool {
         assert!(!f64::is_nan(x), "Encountered NaN value for x");
         assert!(!f64::is_nan(y), "Encountered NaN value for y");
 
-        let x = x.floor() as i64;
-        let y = y.floor() as i64;
+        if x.is_infinite() || y.is_infinite() {
+            return false;
+        }
 
         let mut x0 = self.x0;
         let mut y0 = self.y0;
         let mut x1 = self.x1;
         let mut y1 = self.y1;
 
+        // The quadrants in which the old root ends up, from the innermost to the outermost wrap.
+        let mut wraps = Vec::new();
+
         if x0 > x1 {
-            x0 = x;
-            x1 = x0 + 1;
-            y0 = y;
-            y1 = y0 + 1;
+            x0 = x.floor();
+            x1 = x0 + 1.0;
+            y0 = y.floor();
+            y1 = y0 + 1.0;
         } else {
             // Otherwise, double repeatedly to cover.
-            let mut z = if (x1 - x0).is_positive() { x1 - x0 } else { 1 };
-
-            let node = if matches!(self.root(), Some(&Node::Internal { .. })) {
-                &mut self.root
-            } else {
-                &mut None
-            };
+            let mut z = if x1 > x0 { x1 - x0 } else { 1.0 };
 
             while x0 > x || x >= x1 || y0 > y || y >= y1 {
-                let i = ((y < y0) as usize) << 1 | ((x < x0) as usize);
+                if !z.is_finite() {
+                    return false;
+                }
 
-                let mut children = [None, None, None, None];
-                children[i] = node.take();
-                *node = Some(Box::new(Node::Internal {
-                    children,
-                    value: None,
-                    handle: self.store.insert(Default::default()),
-                }));
+                let i = ((y < y0) as usize) << 1 | ((x < x0) as usize);
+                wraps.push(i);
 
-                z *= 2;
+                z *= 2.0;
                 match i {
                     0 => {
                         x1 = x0 + z;
@@ -62,10 +66,27 @@ impl<Q: Default, T> Quadtree<Q, T> {
             }
         }
 
+        let finite = [x1 - x0, y1 - y0, x0 + x1, y0 + y1]
+            .iter()
+            .all(|v| v.is_finite());
+        if !finite {
+            return false;
+        }
+
+        // Leaves and empty trees only need their extent to be adjusted.
+        if matches!(self.root(), Some(&Node::Internal { .. })) {
+            for i in wraps {
+                let mut children = [None; 4];
+                children[i] = self.root.take();
+                self.root = Some(self.arena.insert(Node::Internal { children }));
+            }
+        }
+
         self.x0 = x0;
         self.y0 = y0;
         self.x1 = x1;
         self.y1 = y1;
+        true
     }
 }
 
@@ -79,7 +100,7 @@ mod test {
     fn sets_a_trivial_extent_if_the_extent_was_undefined() {
         let mut q = Quadtree::default();
         q.cover(1., 2.);
-        assert_eq!(q.extent(), ([1, 2], [2, 3]));
+        assert_eq!(q.extent(), ([1., 2.], [2., 3.]));
     }
 
     #[test]
@@ -87,7 +108,7 @@ mod test {
         let mut q = Quadtree::default();
         q.cover(0., 0.);
         q.cover(1., 2.);
-        assert_eq!(q.extent(), ([0, 0], [4, 4]));
+        assert_eq!(q.extent(), ([0., 0.], [4., 4.]));
     }
 
     #[test]
@@ -108,74 +129,75 @@ mod test {
 
         let mut q = Quadtree::default();
         cover_multiple(&mut q, &[[0., 0.], [2., 2.], [-1., -1.]]);
-        assert_eq!(q.extent(), ([-4, -4], [4, 4]));
+        assert_eq!(q.extent(), ([-4., -4.], [4., 4.]));
 
         let mut q = Quadtree::default();
         cover_multiple(&mut q, &[[0., 0.], [2., 2.], [1., -1.]]);
-        assert_eq!(q.extent(), ([0, -4], [8, 4]));
+        assert_eq!(q.extent(), ([0., -4.], [8., 4.]));
 
         let mut q = Quadtree::default();
         cover_multiple(&mut q, &[[0., 0.], [2., 2.], [3., -1.]]);
-        assert_eq!(q.extent(), ([0, -4], [8, 4]));
+        assert_eq!(q.extent(), ([0., -4.], [8., 4.]));
 
         let mut q = Quadtree::default();
         cover_multiple(&mut q, &[[0., 0.], [2., 2.], [3., 1.]]);
-        assert_eq!(q.extent(), ([0, 0], [4, 4]));
+        assert_eq!(q.extent(), ([0., 0.], [4., 4.]));
 
         let mut q = Quadtree::default();
         cover_multiple(&mut q, &[[0., 0.], [2., 2.], [3., 3.]]);
-        assert_eq!(q.extent(), ([0, 0], [4, 4]));
+        assert_eq!(q.extent(), ([0., 0.], [4., 4.]));
 
         let mut q = Quadtree::default();
         cover_multiple(&mut q, &[[0., 0.], [2., 2.], [1., 3.]]);
-        assert_eq!(q.extent(), ([0, 0], [4, 4]));
+        assert_eq!(q.extent(), ([0., 0.], [4., 4.]));
 
         let mut q = Quadtree::default();
         cover_multiple(&mut q, &[[0., 0.], [2., 2.], [-1., 3.]]);
-        assert_eq!(q.extent(), ([-4, 0], [4, 8]));
+        assert_eq!(q.extent(), ([-4., 0.], [4., 8.]));
 
         let mut q = Quadtree::default();
         cover_multiple(&mut q, &[[0., 0.], [2., 2.], [-1., 1.]]);
-        assert_eq!(q.extent(), ([-4, 0], [4, 8]));
+        assert_eq!(q.extent(), ([-4., 0.], [4., 8.]));
 
         let mut q = Quadtree::default();
         cover_multiple(&mut q, &[[0., 0.], [2., 2.], [-3., -3.]]);
-        assert_eq!(q.extent(), ([-4, -4], [4, 4]));
+        assert_eq!(q.extent(), ([-4., -4.], [4., 4.]));
 
         let mut q = Quadtree::default();
         cover_multiple(&mut q, &[[0., 0.], [2., 2.], [3., -3.]]);
-        assert_eq!(q.extent(), ([0, -4], [8, 4]));
+        assert_eq!(q.extent(), ([0., -4.], [8., 4.]));
 
         let mut q = Quadtree::default();
         cover_multiple(&mut q, &[[0., 0.], [2., 2.], [5., -3.]]);
-        assert_eq!(q.extent(), ([0, -4], [8, 4]));
+        assert_eq!(q.extent(), ([0., -4.], [8., 4.]));
 
         let mut q = Quadtree::default();
         cover_multiple(&mut q, &[[0., 0.], [2., 2.], [5., 3.]]);
-        assert_eq!(q.extent(), ([0, 0], [8, 8]));
+        assert_eq!(q.extent(), ([0., 0.], [8., 8.]));
 
         let mut q = Quadtree::default();
         cover_multiple(&mut q, &[[0., 0.], [2., 2.], [5., 5.]]);
-        assert_eq!(q.extent(), ([0, 0], [8, 8]));
+        assert_eq!(q.extent(), ([0., 0.], [8., 8.]));
 
         let mut q = Quadtree::default();
         cover_multiple(&mut q, &[[0., 0.], [2., 2.], [3., 5.]]);
-        assert_eq!(q.extent(), ([0, 0], [8, 8]));
+        assert_eq!(q.extent(), ([0., 0.], [8., 8.]));
 
         let mut q = Quadtree::default();
         cover_multiple(&mut q, &[[0., 0.], [2., 2.], [-3., 5.]]);
-        assert_eq!(q.extent(), ([-4, 0], [4, 8]));
+        assert_eq!(q.extent(), ([-4., 0.], [4., 8.]));
 
         let mut q = Quadtree::default();
         cover_multiple(&mut q, &[[0., 0.], [2., 2.], [-3., 3.]]);
-        assert_eq!(q.extent(), ([-4, 0], [4, 8]));
+        assert_eq!(q.extent(), ([-4., 0.], [4., 8.]));
 
         let mut q = Quadtree::default();
         cover_multiple(&mut q, &[[0., 0.], [2., 2.], [-1., 1.]]);
-        assert_eq!(q.extent(), ([-4, 0], [4, 8]));
+        assert_eq!(q.extent(), ([-4., 0.], [4., 8.]));
     }
 
     #[test]
+    #[allow(clippy::too_many_lines)]
     fn repeatedly_wraps_the_root_node_if_it_has_children() {
         let mut q = Quadtree::default();
         q.insert_default(0., 0.);
@@ -184,7 +206,7 @@ mod test {
         let mut tmp = q.clone();
         tmp.cover(3., 3.);
         assert!(matches!(
-            tmp.root().unwrap().children().unwrap(),
+            tmp.children_at(&[]).unwrap(),
             [
                 Some(&Node::Leaf { x: 0., y: 0., .. }),
                 None,
@@ -196,9 +218,7 @@ mod test {
         let mut tmp = q.clone();
         tmp.cover(-1., 3.);
         assert!(matches!(
-            tmp.root().unwrap().children().unwrap()[1]
-                .unwrap()
-                .children(),
+            tmp.children_at(&[1]),
             Some([
                 Some(&Node::Leaf { x: 0., y: 0., .. }),
                 None,
@@ -210,9 +230,7 @@ mod test {
         let mut tmp = q.clone();
         tmp.cover(3., -1.);
         assert!(matches!(
-            tmp.root().unwrap().children().unwrap()[2]
-                .unwrap()
-                .children(),
+            tmp.children_at(&[2]),
             Some([
                 Some(&Node::Leaf { x: 0., y: 0., .. }),
                 None,
@@ -224,9 +242,7 @@ mod test {
         let mut tmp = q.clone();
         tmp.cover(-1., -1.);
         assert!(matches!(
-            tmp.root().unwrap().children().unwrap()[3]
-                .unwrap()
-                .children(),
+            tmp.children_at(&[3]),
             Some([
                 Some(&Node::Leaf { x: 0., y: 0., .. }),
                 None,
@@ -238,9 +254,7 @@ mod test {
         let mut tmp = q.clone();
         tmp.cover(5., 5.);
         assert!(matches!(
-            tmp.root().unwrap().children().unwrap()[0]
-                .unwrap()
-                .children(),
+            tmp.children_at(&[0]),
             Some([
                 Some(&Node::Leaf { x: 0., y: 0., .. }),
                 None,
@@ -252,9 +266,7 @@ mod test {
         let mut tmp = q.clone();
         tmp.cover(-3., 5.);
         assert!(matches!(
-            tmp.root().unwrap().children().unwrap()[1]
-                .unwrap()
-                .children(),
+            tmp.children_at(&[1]),
             Some([
                 Some(&Node::Leaf { x: 0., y: 0., .. }),
                 None,
@@ -266,9 +278,7 @@ mod test {
         let mut tmp = q.clone();
         tmp.cover(5., -3.);
         assert!(matches!(
-            tmp.root().unwrap().children().unwrap()[2]
-                .unwrap()
-                .children(),
+            tmp.children_at(&[2]),
             Some([
                 Some(&Node::Leaf { x: 0., y: 0., .. }),
                 None,
@@ -280,9 +290,7 @@ mod test {
         let mut tmp = q.clone();
         tmp.cover(-3., -3.);
         assert!(matches!(
-            tmp.root().unwrap().children().unwrap()[3]
-                .unwrap()
-                .children(),
+            tmp.children_at(&[3]),
             Some([
                 Some(&Node::Leaf { x: 0., y: 0., .. }),
                 None,
@@ -315,9 +323,9 @@ mod test {
 
     #[test]
     fn does_not_wrap_root_node_if_it_is_undefined() {
-        fn cover_root(mut q: Quadtree, p: [f64; 2]) -> Option<Box<Node<(), ()>>> {
+        fn cover_root(mut q: Quadtree, p: [f64; 2]) -> Option<Node<()>> {
             q.cover(p[0], p[1]);
-            q.root
+            q.root().cloned()
         }
 
         let mut q = Quadtree::default();
@@ -334,7 +342,6 @@ mod test {
         assert_eq!(cover_root(q.clone(), [-3., -3.]), None);
     }
 
-    #[ignore = "We currently don't handle extents that exceed `i64::MAX`."]
     #[test]
     fn does_not_crash_on_huge_values() {
         let mut q = Quadtree::default();
@@ -342,5 +349,53 @@ mod test {
         q.insert_default(-1e19, 0.);
         q.insert_default(0., 1e19);
         q.insert_default(0., -1e19);
+        assert_eq!(q.size(), 4);
+    }
+
+    #[test]
+    fn ignores_infinite_values() {
+        let mut q = Quadtree::default();
+        q.cover(0., 0.);
+        q.cover(f64::INFINITY, 2.);
+        q.cover(2., f64::NEG_INFINITY);
+        assert_eq!(q.extent(), ([0., 0.], [1., 1.]));
+    }
+
+    #[test]
+    fn ignores_values_whose_extent_would_overflow() {
+        let mut q = Quadtree::default();
+        q.insert_default(1e308, 0.);
+        q.insert_default(1.5e308, 0.);
+        assert!(q.root().is_none());
+
+        q.insert_default(0., 0.);
+        q.insert_default(1e308, 0.);
+        q.insert_default(1.7e308, 0.);
+        q.insert_default(0., f64::MAX);
+        assert_eq!(q.extent(), ([0., 0.], [1., 1.]));
+        assert_eq!(q.size(), 1);
+
+        q.insert_default(1e300, -1e300);
+        assert_eq!(q.size(), 2);
+        assert!(q.find(1e300, -1e300, 1.0).is_some());
+    }
+
+    #[test]
+    fn handles_sub_unit_coordinates() {
+        let mut q = Quadtree::default();
+        q.insert_default(0.001, 0.001);
+        q.insert_default(0.002, 0.001);
+        assert_eq!(q.extent(), ([0., 0.], [1., 1.]));
+        assert_eq!(q.size(), 2);
+
+        let mut extents = Vec::new();
+        q.visit(|quad| {
+            extents.push(<[f64; 4]>::from(quad.extent()));
+            crate::quadtree::Visit::Continue
+        });
+        assert_eq!(
+            extents.last(),
+            Some(&[0.001953125, 0.0, 0.00390625, 0.001953125])
+        );
     }
 }
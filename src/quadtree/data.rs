@@ -0,0 +1,74 @@
+use super::{
+    arena::{Arena, NodeId},
+    LeafBucket, Node, Quadtree,
+};
+
+/// Iterates over all points of a [`Quadtree`], see [`Quadtree::data`].
+pub struct Data<'a, Q, T> {
+    arena: &'a Arena<Q, T>,
+    stack: Vec<NodeId>,
+    leaf: Option<(f64, f64, &'a LeafBucket<T>, usize)>,
+}
+
+impl<'a, Q, T> Iterator for Data<'a, Q, T> {
+    type Item = (f64, f64, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some((x, y, data, i)) = self.leaf {
+                if let Some(value) = data.get(i) {
+                    self.leaf = Some((x, y, data, i + 1));
+                    return Some((x, y, value));
+                }
+                self.leaf = None;
+            }
+
+            match self.arena.get(self.stack.pop()?) {
+                Node::Leaf { x, y, data } => self.leaf = Some((*x, *y, data, 0)),
+                Node::Internal { children } => {
+                    self.stack.extend(children.iter().rev().flatten());
+                }
+            }
+        }
+    }
+}
+
+impl<Q: Default, T> Quadtree<Q, T> {
+    /// Returns all points in the tree, including coincident ones, in pre-order.
+    pub fn data(&self) -> Data<'_, Q, T> {
+        Data {
+            arena: &self.arena,
+            stack: self.root.into_iter().collect(),
+            leaf: None,
+        }
+    }
+
+    /// Returns the number of points in the tree, including coincident ones.
+    pub fn size(&self) -> usize {
+        self.data().count()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    type Quadtree = super::Quadtree<(), usize>;
+
+    #[test]
+    fn returns_all_points() {
+        let mut q = Quadtree::default();
+        assert_eq!(q.size(), 0);
+        assert_eq!(q.data().next(), None);
+
+        q.add_all([(0.0, 0.0, 0), (1.0, 1.0, 1), (1.0, 1.0, 2), (0.5, 0.8, 3)]);
+        assert_eq!(q.size(), 4);
+        assert_eq!(
+            q.data().collect::<Vec<_>>(),
+            [
+                (0.0, 0.0, &0),
+                (0.5, 0.8, &3),
+                (1.0, 1.0, &1),
+                (1.0, 1.0, &2)
+            ]
+        );
+    }
+}
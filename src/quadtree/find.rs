@@ -0,0 +1,102 @@
+use super::{Node, Quadtree};
+
+impl<Q: Default, T> Quadtree<Q, T> {
+    /// Returns the point that is closest to `(x, y)` within the given `radius`.
+    ///
+    /// Use `f64::INFINITY` to search without a radius. If several points coincide, the one that
+    /// was inserted first is returned.
+    pub fn find(&self, x: f64, y: f64, radius: f64) -> Option<(f64, f64, &T)> {
+        let root = self.root?;
+
+        let mut x0 = self.x0;
+        let mut y0 = self.y0;
+        let mut x3 = self.x1;
+        let mut y3 = self.y1;
+        let mut radius_2 = radius * radius;
+        if radius.is_finite() {
+            x0 = x - radius;
+            y0 = y - radius;
+            x3 = x + radius;
+            y3 = y + radius;
+        }
+
+        let mut found = None;
+        let mut stack = vec![(root, [self.x0, self.y0, self.x1, self.y1])];
+
+        while let Some((id, [x1, y1, x2, y2])) = stack.pop() {
+            // Stop searching if this quad can't contain a closer point.
+            if x1 > x3 || y1 > y3 || x2 < x0 || y2 < y0 {
+                continue;
+            }
+
+            match self.arena.get(id) {
+                Node::Leaf { x: px, y: py, data } => {
+                    let dx = x - px;
+                    let dy = y - py;
+                    let d2 = dx * dx + dy * dy;
+                    if d2 < radius_2 {
+                        let d = d2.sqrt();
+                        radius_2 = d2;
+                        x0 = x - d;
+                        y0 = y - d;
+                        x3 = x + d;
+                        y3 = y + d;
+                        found = Some((*px, *py, &data.first));
+                    }
+                }
+                Node::Internal { children } => {
+                    let xm = (x1 + x2) / 2.0;
+                    let ym = (y1 + y2) / 2.0;
+                    let extents = [
+                        [x1, y1, xm, ym],
+                        [xm, y1, x2, ym],
+                        [x1, ym, xm, y2],
+                        [xm, ym, x2, y2],
+                    ];
+
+                    // Visit the closest child first, which is pushed onto the stack last.
+                    let closest = (usize::from(y >= ym) << 1) | usize::from(x >= xm);
+                    for i in (0..4).map(|i| i ^ closest).rev() {
+                        if let Some(child) = children[i] {
+                            stack.push((child, extents[i]));
+                        }
+                    }
+                }
+            }
+        }
+
+        found
+    }
+}
+
+#[cfg(test)]
+mod test {
+    type Quadtree = super::Quadtree<(), usize>;
+
+    #[test]
+    fn returns_the_closest_point() {
+        let q = Quadtree::grid();
+        assert_eq!(q.find(0.1, 0.1, f64::INFINITY), Some((0.0, 0.0, &0)));
+        assert_eq!(q.find(7.1, 7.1, f64::INFINITY), Some((7.0, 7.0, &77)));
+        assert_eq!(q.find(0.1, 8.9, f64::INFINITY), Some((0.0, 9.0, &90)));
+        assert_eq!(q.find(20.0, -3.0, f64::INFINITY), Some((9.0, 0.0, &9)));
+    }
+
+    #[test]
+    fn respects_the_search_radius() {
+        let q = Quadtree::grid();
+        assert_eq!(q.find(4.5, 4.5, 0.5), None);
+        assert_eq!(q.find(4.4, 4.4, 0.6), Some((4.0, 4.0, &44)));
+        assert_eq!(q.find(20.0, 20.0, 5.0), None);
+    }
+
+    #[test]
+    fn handles_coincident_points_and_empty_trees() {
+        let mut q = Quadtree::default();
+        assert_eq!(q.find(0.0, 0.0, f64::INFINITY), None);
+
+        q.add_all([(1.0, 1.0, 0), (1.0, 1.0, 1), (3.0, 3.0, 2)]);
+        assert_eq!(q.find(0.0, 0.0, f64::INFINITY), Some((1.0, 1.0, &0)));
+        assert_eq!(q.find(1.0, 1.0, 0.1), Some((1.0, 1.0, &0)));
+    }
+}
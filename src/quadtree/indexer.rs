@@ -1,4 +1,4 @@
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct Indexer {
     x0: f64,
     y0: f64,
@@ -20,6 +20,27 @@ impl Indexer {
         }
     }
 
+    /// Checks if `(x, y)` lies within the current cell.
+    pub fn contains(&self, x: f64, y: f64) -> bool {
+        self.x0 <= x && x < self.x1 && self.y0 <= y && y < self.y1
+    }
+
+    /// Checks if the midpoint still splits the current cell at the precision of `f64`.
+    pub fn can_split(&self) -> bool {
+        (self.x0 < self.xm && self.xm < self.x1) || (self.y0 < self.ym && self.ym < self.y1)
+    }
+
+    /// Checks if two points can't be told apart, because they stay in the same cell until it can
+    /// no longer be split. Such points share a leaf.
+    pub fn merges(mut self, [ax, ay]: [f64; 2], [bx, by]: [f64; 2]) -> bool {
+        while self.can_split() {
+            if self.get(ax, ay) != self.get_and_descend(bx, by) {
+                return false;
+            }
+        }
+        true
+    }
+
     pub fn get(&self, x: f64, y: f64) -> usize {
         let right = x >= self.xm;
         let bottom = y >= self.ym;
@@ -84,4 +105,23 @@ mod test {
         assert_eq!(ix.get(0.1, 0.1), 0);
         assert_eq!(ix.get(0.4, 0.4), 3);
     }
+
+    #[test]
+    fn detects_cells_that_can_not_be_split() {
+        let mut ix = Indexer::with_extent([0.0, 0.0], [1.0, 1.0]);
+        assert!(ix.can_split());
+        for _ in 0..1074 {
+            ix.get_and_descend(0.0, 0.0);
+        }
+        assert_eq!(ix.extent(), ([0.0, 0.0], [5e-324, 5e-324]));
+        assert!(!ix.can_split());
+    }
+
+    #[test]
+    fn merges_points_that_can_not_be_told_apart() {
+        // The midpoint of the x-axis rounds to one of its bounds, so only the y-axis is split.
+        let ix = Indexer::with_extent([1.0, 0.0], [1.0 + f64::EPSILON, 1.0]);
+        assert!(ix.clone().merges([1.0, 0.5], [1.0 + f64::EPSILON, 0.5]));
+        assert!(!ix.merges([1.0, 0.25], [1.0, 0.75]));
+    }
 }
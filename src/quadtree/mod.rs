@@ -1,125 +1,112 @@
 mod add;
 mod add_all;
+mod aggregate;
+mod arena;
+mod copy;
 mod cover;
+mod data;
+mod find;
 mod indexer;
+mod nearest;
+mod point;
 mod quad;
-mod store;
+mod range;
+mod relocate;
+mod remove;
 mod visit;
 mod visit_after;
 
-use store::{Handle, Store};
+use arena::{Arena, NodeId};
 
+pub use aggregate::Aggregator;
+pub use data::Data;
+pub use point::Point;
 pub use quad::{Entry, Quad};
+pub use range::Range;
 pub use visit::Visit;
 
+/// The points of a leaf, which all share the same position.
+///
+/// The first point is stored inline, so that leaves with a single point don't allocate.
 #[derive(Clone, Debug, PartialEq, Eq)]
-pub struct LeafEntry<T> {
-    value: T,
-    next: Option<Box<LeafEntry<T>>>,
+pub(super) struct LeafBucket<T> {
+    first: T,
+    others: Vec<T>,
 }
 
-impl<T> LeafEntry<T> {
-    fn new(data: T) -> Self {
+impl<T> LeafBucket<T> {
+    fn new(value: T) -> Self {
         Self {
-            value: data,
-            next: None,
+            first: value,
+            others: Vec::new(),
         }
     }
 
-    fn insert(&mut self, data: T) {
-        let mut node = self;
-        loop {
-            if let Some(ref mut next) = node.next {
-                node = next;
-            } else {
-                node.next = Some(Box::new(Self::new(data)));
-                return;
-            }
-        }
+    fn push(&mut self, value: T) {
+        self.others.push(value);
     }
 
-    fn iter(&self) -> impl Iterator<Item = &T> {
-        LeafListIterator { next: Some(self) }
+    fn get(&self, i: usize) -> Option<&T> {
+        match i {
+            0 => Some(&self.first),
+            i => self.others.get(i - 1),
+        }
     }
-}
-
-struct LeafListIterator<'a, T> {
-    next: Option<&'a LeafEntry<T>>,
-}
 
-impl<'a, T> Iterator for LeafListIterator<'a, T> {
-    type Item = &'a T;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        let next = self.next;
-        self.next = next.and_then(|node| node.next.as_deref());
-        next.map(|node| &node.value)
+    fn iter(&self) -> impl Iterator<Item = &T> {
+        std::iter::once(&self.first).chain(&self.others)
     }
 }
 
 #[derive(Clone, Debug, PartialEq)]
-pub(super) enum Node<Q, T> {
-    Leaf {
-        x: f64,
-        y: f64,
-        data: LeafEntry<T>,
-        handle: Handle<Q>,
-    },
-    Internal {
-        value: Option<T>,
-        children: [Option<Box<Node<Q, T>>>; 4],
-        handle: Handle<Q>,
-    },
+pub(super) enum Node<T> {
+    Leaf { x: f64, y: f64, data: LeafBucket<T> },
+    Internal { children: [Option<NodeId>; 4] },
 }
 
-impl<Q, T> Node<Q, T> {
-    #[cfg(test)]
-    fn leaf(&self) -> Option<&LeafEntry<T>> {
-        match self {
-            Self::Leaf { data, .. } => Some(data),
-            _ => None,
+impl<T> Node<T> {
+    fn leaf(x: f64, y: f64, value: T) -> Self {
+        Self::Leaf {
+            x,
+            y,
+            data: LeafBucket::new(value),
         }
     }
 
-    #[cfg(test)]
-    fn children(&self) -> Option<[Option<&Self>; 4]> {
-        match self {
-            Self::Leaf { .. } => None,
-            Self::Internal { children, .. } => Some([
-                children[0].as_deref(),
-                children[1].as_deref(),
-                children[2].as_deref(),
-                children[3].as_deref(),
-            ]),
+    fn empty() -> Self {
+        Self::Internal {
+            children: [None; 4],
         }
     }
 
-    fn handle(&self) -> Handle<Q> {
+    #[cfg(test)]
+    fn bucket(&self) -> Option<&LeafBucket<T>> {
         match self {
-            Self::Leaf { handle, .. } | Self::Internal { handle, .. } => *handle,
+            Self::Leaf { data, .. } => Some(data),
+            Self::Internal { .. } => None,
         }
     }
 }
 
 #[derive(Clone, Debug)]
 pub struct Quadtree<Q: Default = (), T = ()> {
-    x0: i64,
-    y0: i64,
-    x1: i64,
-    y1: i64,
-    root: Option<Box<Node<Q, T>>>,
-    store: Store<Q>,
+    x0: f64,
+    y0: f64,
+    x1: f64,
+    y1: f64,
+    root: Option<NodeId>,
+    arena: Arena<Q, T>,
 }
 
 impl<Q: Default, T> Default for Quadtree<Q, T> {
     fn default() -> Self {
         Self {
-            x0: i64::MAX,
-            y0: 0,
-            x1: i64::MIN,
-            y1: 0,
+            x0: f64::INFINITY,
+            y0: 0.0,
+            x1: f64::NEG_INFINITY,
+            y1: 0.0,
             root: None,
-            store: Store::new(),
+            arena: Arena::default(),
         }
     }
 }
@@ -127,9 +114,7 @@ impl<Q: Default, T> Default for Quadtree<Q, T> {
 impl<Q: Default, T> Quadtree<Q, T> {
     pub fn from_particles(particles: impl Iterator<Item = (f64, f64, T)>) -> Self {
         let mut tree = Self::default();
-        for (x, y, value) in particles {
-            tree.insert(x, y, value);
-        }
+        tree.add_all(particles);
         tree
     }
 
@@ -140,33 +125,47 @@ impl<Q: Default, T> Quadtree<Q, T> {
         tree
     }
 
-    pub fn extent(&self) -> ([i64; 2], [i64; 2]) {
+    /// Removes all points and resets the extent, but keeps the allocated memory for reuse.
+    pub fn clear(&mut self) {
+        let arena = std::mem::take(&mut self.arena);
+        *self = Self {
+            arena,
+            ..Self::default()
+        };
+        self.arena.clear();
+    }
+
+    pub fn extent(&self) -> ([f64; 2], [f64; 2]) {
         ([self.x0, self.y0], [self.x1, self.y1])
     }
 
-    fn root(&self) -> Option<&Node<Q, T>> {
-        self.root.as_deref()
+    fn root(&self) -> Option<&Node<T>> {
+        self.root.map(|id| self.arena.get(id))
     }
-}
 
-pub(crate) fn create_empty_internal<Q: Default, T>(store: &mut Store<Q>) -> Node<Q, T> {
-    Node::Internal {
-        children: [None, None, None, None],
-        value: None,
-        handle: store.insert(Default::default()),
+    /// Returns the children of the internal node that is reached by following `path`.
+    #[cfg(test)]
+    fn children_at(&self, path: &[usize]) -> Option<[Option<&Node<T>>; 4]> {
+        let mut id = self.root?;
+        for &i in path {
+            let Node::Internal { children } = self.arena.get(id) else {
+                return None;
+            };
+            id = children[i]?;
+        }
+        match self.arena.get(id) {
+            Node::Internal { children } => Some(children.map(|c| c.map(|c| self.arena.get(c)))),
+            Node::Leaf { .. } => None,
+        }
     }
 }
 
-pub(crate) fn create_leaf<Q: Default, T>(
-    store: &mut Store<Q>,
-    x: f64,
-    y: f64,
-    value: T,
-) -> Node<Q, T> {
-    Node::Leaf {
-        x,
-        y,
-        data: LeafEntry::new(value),
-        handle: store.insert(Default::default()),
+#[cfg(test)]
+impl Quadtree<(), usize> {
+    /// Creates a tree with the points of a 10 by 10 grid, numbered row by row.
+    fn grid() -> Self {
+        let mut q = Self::default();
+        q.add_all((0..100).map(|i| ((i % 10) as f64, (i / 10) as f64, i)));
+        q
     }
 }
@@ -0,0 +1,174 @@
+use std::{cmp::Ordering, collections::BinaryHeap};
+
+use super::{arena::NodeId, Node, Quadtree};
+
+enum Candidate<'a, T> {
+    Quad(NodeId, [f64; 4]),
+    Point(f64, f64, &'a T),
+}
+
+/// An entry of the priority queue, ordered such that the closest candidate is popped first.
+struct Queued<'a, T> {
+    distance_2: f64,
+    /// Breaks ties in insertion order, which keeps coincident points in their original order.
+    sequence: usize,
+    candidate: Candidate<'a, T>,
+}
+
+impl<T> PartialEq for Queued<'_, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<T> Eq for Queued<'_, T> {}
+
+impl<T> PartialOrd for Queued<'_, T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for Queued<'_, T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .distance_2
+            .total_cmp(&self.distance_2)
+            .then_with(|| other.sequence.cmp(&self.sequence))
+    }
+}
+
+/// Returns the squared distance from `(x, y)` to the closest point of the extent.
+fn min_distance_2(x: f64, y: f64, [x0, y0, x1, y1]: [f64; 4]) -> f64 {
+    let dx = f64::max(f64::max(x0 - x, 0.0), x - x1);
+    let dy = f64::max(f64::max(y0 - y, 0.0), y - y1);
+    dx * dx + dy * dy
+}
+
+impl<Q: Default, T> Quadtree<Q, T> {
+    /// Returns up to `k` points that are closest to `(x, y)` within the given `radius`, sorted
+    /// by their distance.
+    ///
+    /// Use `f64::INFINITY` to search without a radius, and `usize::MAX` to return all points
+    /// within the radius. Coincident points are returned in the order in which they were
+    /// inserted.
+    pub fn nearest(&self, x: f64, y: f64, k: usize, radius: f64) -> Vec<(f64, f64, &T)> {
+        // `k` is only an upper bound, so we don't preallocate for it.
+        let mut result = Vec::new();
+        let Some(root) = self.root else {
+            return result;
+        };
+
+        let radius_2 = radius * radius;
+        let mut sequence = 0;
+        let mut heap = BinaryHeap::new();
+        let extent = [self.x0, self.y0, self.x1, self.y1];
+        heap.push(Queued {
+            distance_2: min_distance_2(x, y, extent),
+            sequence,
+            candidate: Candidate::Quad(root, extent),
+        });
+
+        while let Some(Queued {
+            distance_2,
+            candidate,
+            ..
+        }) = heap.pop()
+        {
+            if result.len() >= k || distance_2 > radius_2 {
+                break;
+            }
+
+            let mut push = |distance_2: f64, candidate| {
+                if distance_2 <= radius_2 {
+                    sequence += 1;
+                    heap.push(Queued {
+                        distance_2,
+                        sequence,
+                        candidate,
+                    });
+                }
+            };
+
+            match candidate {
+                // Every remaining candidate is at least as far away as this point.
+                Candidate::Point(px, py, data) => result.push((px, py, data)),
+                Candidate::Quad(id, [x0, y0, x1, y1]) => match self.arena.get(id) {
+                    Node::Leaf { x: px, y: py, data } => {
+                        let d2 = (x - px) * (x - px) + (y - py) * (y - py);
+                        for value in data.iter() {
+                            push(d2, Candidate::Point(*px, *py, value));
+                        }
+                    }
+                    Node::Internal { children } => {
+                        let xm = (x0 + x1) / 2.0;
+                        let ym = (y0 + y1) / 2.0;
+                        let extents = [
+                            [x0, y0, xm, ym],
+                            [xm, y0, x1, ym],
+                            [x0, ym, xm, y1],
+                            [xm, ym, x1, y1],
+                        ];
+                        for (child, extent) in children.iter().zip(extents) {
+                            if let Some(child) = *child {
+                                push(min_distance_2(x, y, extent), Candidate::Quad(child, extent));
+                            }
+                        }
+                    }
+                },
+            }
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod test {
+    type Quadtree = super::Quadtree<(), usize>;
+
+    #[test]
+    fn returns_the_k_closest_points_sorted_by_distance() {
+        let q = Quadtree::grid();
+        let result = q.nearest(4.2, 4.3, 4, f64::INFINITY);
+        assert_eq!(
+            result,
+            [
+                (4.0, 4.0, &44),
+                (4.0, 5.0, &54),
+                (5.0, 4.0, &45),
+                (5.0, 5.0, &55)
+            ]
+        );
+
+        // Compares against a brute-force search.
+        let (x, y) = (2.7, 6.3);
+        let mut expected = q.data().collect::<Vec<_>>();
+        expected.sort_by(|a, b| {
+            let da = (a.0 - x).hypot(a.1 - y);
+            let db = (b.0 - x).hypot(b.1 - y);
+            da.total_cmp(&db)
+        });
+        let result = q.nearest(x, y, 10, f64::INFINITY);
+        for (a, b) in result.iter().zip(&expected) {
+            approx::assert_abs_diff_eq!((a.0 - x).hypot(a.1 - y), (b.0 - x).hypot(b.1 - y));
+        }
+        assert_eq!(result.len(), 10);
+    }
+
+    #[test]
+    fn respects_the_radius_and_coincident_points() {
+        let q = Quadtree::grid();
+        assert_eq!(q.nearest(4.0, 4.0, 10, 1.0).len(), 5);
+        assert_eq!(q.nearest(4.0, 4.0, usize::MAX, 1.5).len(), 9);
+        assert!(q.nearest(20.0, 20.0, 10, 5.0).is_empty());
+        assert!(q.nearest(0.0, 0.0, 0, f64::INFINITY).is_empty());
+
+        let mut q = Quadtree::default();
+        q.add_all([(1.0, 1.0, 0), (1.0, 1.0, 1), (3.0, 3.0, 2)]);
+        assert_eq!(
+            q.nearest(0.0, 0.0, 5, f64::INFINITY),
+            [(1.0, 1.0, &0), (1.0, 1.0, &1), (3.0, 3.0, &2)]
+        );
+    }
+}
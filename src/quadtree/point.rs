@@ -0,0 +1,108 @@
+use super::Quadtree;
+
+/// Provides the position of a value, so that it can be stored in a [`Quadtree`] directly.
+pub trait Point {
+    fn x(&self) -> f64;
+    fn y(&self) -> f64;
+}
+
+impl Point for [f64; 2] {
+    fn x(&self) -> f64 {
+        self[0]
+    }
+
+    fn y(&self) -> f64 {
+        self[1]
+    }
+}
+
+impl Point for (f64, f64) {
+    fn x(&self) -> f64 {
+        self.0
+    }
+
+    fn y(&self) -> f64 {
+        self.1
+    }
+}
+
+impl<P: Point + ?Sized> Point for &P {
+    fn x(&self) -> f64 {
+        (**self).x()
+    }
+
+    fn y(&self) -> f64 {
+        (**self).y()
+    }
+}
+
+impl<Q: Default, T: Point> Quadtree<Q, T> {
+    pub fn from_points(points: impl IntoIterator<Item = T>) -> Self {
+        let mut tree = Self::default();
+        tree.add_points(points);
+        tree
+    }
+
+    /// Inserts all points, using their own position, see [`Quadtree::add_all`].
+    pub fn add_points(&mut self, points: impl IntoIterator<Item = T>) {
+        self.add_all(points.into_iter().map(|p| (p.x(), p.y(), p)));
+    }
+}
+
+impl<Q: Default, T> Quadtree<Q, T> {
+    /// Builds a tree over arbitrary values, whose positions are given by the `x` and `y` accessors.
+    pub fn from_points_with(
+        points: impl IntoIterator<Item = T>,
+        x: impl Fn(&T) -> f64,
+        y: impl Fn(&T) -> f64,
+    ) -> Self {
+        let mut tree = Self::default();
+        tree.add_all(points.into_iter().map(|p| (x(&p), y(&p), p)));
+        tree
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    struct City {
+        name: &'static str,
+        location: [f64; 2],
+    }
+
+    impl Point for City {
+        fn x(&self) -> f64 {
+            self.location[0]
+        }
+
+        fn y(&self) -> f64 {
+            self.location[1]
+        }
+    }
+
+    #[test]
+    fn stores_user_types() {
+        let cities = [
+            City {
+                name: "a",
+                location: [0.0, 0.0],
+            },
+            City {
+                name: "b",
+                location: [5.0, 3.0],
+            },
+        ];
+
+        let q = Quadtree::<(), &City>::from_points(&cities);
+        let (x, y, city) = q.find(4.0, 4.0, f64::INFINITY).unwrap();
+        assert_eq!((x, y, city.name), (5.0, 3.0, "b"));
+
+        let q = Quadtree::<(), usize>::from_points_with(
+            0..cities.len(),
+            |&i| cities[i].location[0],
+            |&i| cities[i].location[1],
+        );
+        assert_eq!(q.find(1.0, 1.0, f64::INFINITY), Some((0.0, 0.0, &0)));
+    }
+}
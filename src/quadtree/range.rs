@@ -0,0 +1,170 @@
+use crate::extent::Extent;
+
+use super::{
+    arena::{Arena, NodeId},
+    LeafBucket, Node, Quadtree,
+};
+
+#[derive(Clone, Copy, Debug)]
+enum Region {
+    Rect { x0: f64, y0: f64, x1: f64, y1: f64 },
+    Circle { x: f64, y: f64, radius: f64 },
+}
+
+impl Region {
+    fn intersects(&self, extent: Extent) -> bool {
+        match *self {
+            Self::Rect { x0, y0, x1, y1 } => {
+                extent.x0 <= x1 && extent.x1 >= x0 && extent.y0 <= y1 && extent.y1 >= y0
+            }
+            Self::Circle { x, y, radius } => {
+                let dx = f64::max(f64::max(extent.x0 - x, 0.0), x - extent.x1);
+                let dy = f64::max(f64::max(extent.y0 - y, 0.0), y - extent.y1);
+                dx * dx + dy * dy <= radius * radius
+            }
+        }
+    }
+
+    fn contains(&self, px: f64, py: f64) -> bool {
+        match *self {
+            Self::Rect { x0, y0, x1, y1 } => x0 <= px && px <= x1 && y0 <= py && py <= y1,
+            Self::Circle { x, y, radius } => {
+                (px - x) * (px - x) + (py - y) * (py - y) <= radius * radius
+            }
+        }
+    }
+}
+
+/// Iterates over the points of a [`Quadtree`] that lie in a region, see
+/// [`Quadtree::within_rect`] and [`Quadtree::within_circle`].
+pub struct Range<'a, Q, T> {
+    region: Region,
+    arena: &'a Arena<Q, T>,
+    stack: Vec<(NodeId, Extent)>,
+    leaf: Option<(f64, f64, &'a LeafBucket<T>, usize)>,
+}
+
+impl<'a, Q, T> Iterator for Range<'a, Q, T> {
+    type Item = (f64, f64, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some((x, y, data, i)) = self.leaf {
+                if let Some(value) = data.get(i) {
+                    self.leaf = Some((x, y, data, i + 1));
+                    return Some((x, y, value));
+                }
+                self.leaf = None;
+            }
+
+            let (id, extent) = self.stack.pop()?;
+            match self.arena.get(id) {
+                Node::Leaf { x, y, data } => {
+                    if self.region.contains(*x, *y) {
+                        self.leaf = Some((*x, *y, data, 0));
+                    }
+                }
+                Node::Internal { children } => {
+                    let xm = (extent.x0 + extent.x1) / 2.0;
+                    let ym = (extent.y0 + extent.y1) / 2.0;
+                    let extents: [Extent; 4] = [
+                        [extent.x0, extent.y0, xm, ym].into(),
+                        [xm, extent.y0, extent.x1, ym].into(),
+                        [extent.x0, ym, xm, extent.y1].into(),
+                        [xm, ym, extent.x1, extent.y1].into(),
+                    ];
+                    for (child, extent) in children.iter().zip(extents).rev() {
+                        if let Some(child) = *child {
+                            // We skip quads that don't overlap with the region.
+                            if self.region.intersects(extent) {
+                                self.stack.push((child, extent));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<Q: Default, T> Quadtree<Q, T> {
+    fn range(&self, region: Region) -> Range<'_, Q, T> {
+        let extent: Extent = [self.x0, self.y0, self.x1, self.y1].into();
+        Range {
+            region,
+            arena: &self.arena,
+            stack: self
+                .root
+                .filter(|_| region.intersects(extent))
+                .map(|root| (root, extent))
+                .into_iter()
+                .collect(),
+            leaf: None,
+        }
+    }
+
+    /// Returns all points that lie in the rectangle from `min` to `max`, including its boundary.
+    pub fn within_rect(&self, min: [f64; 2], max: [f64; 2]) -> Range<'_, Q, T> {
+        self.range(Region::Rect {
+            x0: min[0],
+            y0: min[1],
+            x1: max[0],
+            y1: max[1],
+        })
+    }
+
+    /// Returns all points whose distance to `(x, y)` is at most `radius`.
+    pub fn within_circle(&self, x: f64, y: f64, radius: f64) -> Range<'_, Q, T> {
+        self.range(Region::Circle { x, y, radius })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    type Quadtree = super::Quadtree<(), usize>;
+
+    #[test]
+    fn returns_points_in_rectangles() {
+        let q = Quadtree::grid();
+
+        let mut result = q
+            .within_rect([2.0, 3.0], [3.5, 4.0])
+            .map(|(_, _, &i)| i)
+            .collect::<Vec<_>>();
+        result.sort_unstable();
+        assert_eq!(result, [32, 33, 42, 43]);
+
+        assert_eq!(q.within_rect([20.0, 20.0], [30.0, 30.0]).count(), 0);
+        assert_eq!(q.within_rect([-1.0, -1.0], [10.0, 10.0]).count(), 100);
+    }
+
+    #[test]
+    fn returns_points_in_circles() {
+        let q = Quadtree::grid();
+
+        let mut result = q
+            .within_circle(5.0, 5.0, 1.0)
+            .map(|(_, _, &i)| i)
+            .collect::<Vec<_>>();
+        result.sort_unstable();
+        assert_eq!(result, [45, 54, 55, 56, 65]);
+
+        // Compares against a brute-force search.
+        let (x, y, r) = (3.3, 7.1, 2.5);
+        let expected = q
+            .data()
+            .filter(|(px, py, _)| (px - x).hypot(py - y) <= r)
+            .count();
+        assert_eq!(q.within_circle(x, y, r).count(), expected);
+    }
+
+    #[test]
+    fn includes_coincident_points() {
+        let mut q = Quadtree::default();
+        q.add_all([(1.0, 1.0, 0), (1.0, 1.0, 1), (3.0, 3.0, 2)]);
+        assert_eq!(
+            q.within_circle(1.0, 1.0, 0.5).collect::<Vec<_>>(),
+            [(1.0, 1.0, &0), (1.0, 1.0, &1)]
+        );
+    }
+}
@@ -0,0 +1,85 @@
+use super::{indexer::Indexer, Node, Quadtree};
+
+impl<Q: Default, T: PartialEq> Quadtree<Q, T> {
+    /// Moves a value that was inserted at `(x, y)` to `(to_x, to_y)`.
+    ///
+    /// If the value is the only point of its leaf and stays within the cell of that leaf, it is
+    /// moved in place. Otherwise, it is removed and inserted again. Returns `false` if the value
+    /// was not found, in which case nothing is inserted.
+    pub fn relocate(&mut self, x: f64, y: f64, to_x: f64, to_y: f64, value: T) -> bool {
+        let Some(mut id) = self.root else {
+            return false;
+        };
+
+        let mut ix = Indexer::with_extent([self.x0, self.y0], [self.x1, self.y1]);
+        while let Node::Internal { children } = self.arena.get(id) {
+            match children[ix.get_and_descend(x, y)] {
+                Some(child) => id = child,
+                None => return false,
+            }
+        }
+
+        if let Node::Leaf { x: px, y: py, data } = self.arena.get_mut(id) {
+            let alone = *px == x && *py == y && data.others.is_empty() && data.first == value;
+            if alone && ix.contains(to_x, to_y) {
+                *px = to_x;
+                *py = to_y;
+                return true;
+            }
+        }
+
+        if !self.remove(x, y, &value) {
+            return false;
+        }
+        self.insert(to_x, to_y, value);
+        true
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Node;
+
+    type Quadtree = super::Quadtree<(), usize>;
+
+    #[test]
+    fn moves_points_within_their_cell_in_place() {
+        let mut q = Quadtree::with_extent([0., 0.], [1., 1.]);
+        q.add_all([(0.2, 0.2, 0), (1.8, 1.8, 1)]);
+        let slots = q.arena.len();
+
+        assert!(q.relocate(0.2, 0.2, 0.6, 0.4, 0));
+        assert!(matches!(
+            q.children_at(&[]).unwrap(),
+            [
+                Some(&Node::Leaf { x: 0.6, y: 0.4, .. }),
+                None,
+                None,
+                Some(_)
+            ]
+        ));
+        assert_eq!(q.arena.len(), slots);
+        assert!(!q.relocate(0.2, 0.2, 0.6, 0.4, 0));
+        assert!(!q.relocate(1.8, 1.8, 0.6, 0.4, 0));
+    }
+
+    #[test]
+    fn rebuckets_points_that_leave_their_cell() {
+        let mut q = Quadtree::with_extent([0., 0.], [1., 1.]);
+        q.add_all([(0.2, 0.2, 0), (1.8, 1.8, 1), (1.8, 1.8, 2)]);
+
+        // Crosses into the cell of another leaf.
+        assert!(q.relocate(0.2, 0.2, 1.6, 1.6, 0));
+        assert!(matches!(
+            q.children_at(&[]).unwrap(),
+            [None, None, None, Some(&Node::Internal { .. })]
+        ));
+
+        // Leaves a shared bucket, and the extent of the tree.
+        assert!(q.relocate(1.8, 1.8, 6.0, 1.0, 2));
+        assert_eq!(q.extent(), ([0., 0.], [8., 8.]));
+        assert_eq!(q.find(6.0, 1.0, 0.1), Some((6.0, 1.0, &2)));
+        assert_eq!(q.find(1.8, 1.8, 0.01), Some((1.8, 1.8, &1)));
+        assert_eq!(q.size(), 3);
+    }
+}
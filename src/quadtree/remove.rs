@@ -0,0 +1,217 @@
+use super::{
+    arena::{Arena, NodeId},
+    indexer::Indexer,
+    LeafBucket, Node, Quadtree,
+};
+
+/// The outcome of removing a value from the points of a leaf.
+enum Unlinked {
+    NotFound,
+    Removed,
+    /// The last value of the leaf was removed.
+    Empty,
+}
+
+impl<T: PartialEq> LeafBucket<T> {
+    fn remove(&mut self, value: &T) -> Unlinked {
+        if self.first == *value {
+            if self.others.is_empty() {
+                return Unlinked::Empty;
+            }
+            // Keeps the remaining points in the order in which they were inserted.
+            self.first = self.others.remove(0);
+            return Unlinked::Removed;
+        }
+
+        match self.others.iter().position(|other| other == value) {
+            Some(i) => {
+                self.others.remove(i);
+                Unlinked::Removed
+            }
+            None => Unlinked::NotFound,
+        }
+    }
+}
+
+/// The outcome of removing a value from a subtree.
+enum Removal {
+    NotFound,
+    /// The value was removed, and the node takes the place of the subtree in its parent.
+    Replaced(Option<NodeId>),
+}
+
+/// Removes the value from the subtree at `id`.
+fn remove_from<Q, T: PartialEq>(
+    arena: &mut Arena<Q, T>,
+    id: NodeId,
+    mut ix: Indexer,
+    x: f64,
+    y: f64,
+    value: &T,
+) -> Removal {
+    let i = match arena.get_mut(id) {
+        Node::Leaf { x: px, y: py, data } => {
+            if (*px != x || *py != y) && !ix.merges([*px, *py], [x, y]) {
+                return Removal::NotFound;
+            }
+            return match data.remove(value) {
+                Unlinked::NotFound => Removal::NotFound,
+                Unlinked::Removed => Removal::Replaced(Some(id)),
+                Unlinked::Empty => {
+                    arena.remove(id);
+                    Removal::Replaced(None)
+                }
+            };
+        }
+        Node::Internal { .. } => ix.get_and_descend(x, y),
+    };
+
+    let Some(child) = arena.children_mut(id)[i] else {
+        return Removal::NotFound;
+    };
+    let Removal::Replaced(replacement) = remove_from(arena, child, ix, x, y, value) else {
+        return Removal::NotFound;
+    };
+    arena.children_mut(id)[i] = replacement;
+
+    let children = *arena.children_mut(id);
+    let mut remaining = children.iter().flatten();
+    match (remaining.next(), remaining.next()) {
+        (None, _) => {
+            arena.remove(id);
+            Removal::Replaced(None)
+        }
+        // An internal node with a single leaf is replaced by that leaf.
+        (Some(&only), None) if matches!(arena.get(only), Node::Leaf { .. }) => {
+            arena.remove(id);
+            Removal::Replaced(Some(only))
+        }
+        _ => Removal::Replaced(Some(id)),
+    }
+}
+
+impl<Q: Default, T: PartialEq> Quadtree<Q, T> {
+    /// Removes a value that was inserted at `(x, y)`.
+    ///
+    /// Returns `true` if the value was found. If multiple equal values were inserted at the same
+    /// position, only the first one is removed. The extent of the tree is not changed.
+    ///
+    /// Values that were merged into the leaf of another point, see [`Quadtree::insert`], are
+    /// found by the position at which they were inserted.
+    pub fn remove(&mut self, x: f64, y: f64, value: &T) -> bool {
+        let Some(root) = self.root else {
+            return false;
+        };
+
+        let ix = Indexer::with_extent([self.x0, self.y0], [self.x1, self.y1]);
+        match remove_from(&mut self.arena, root, ix, x, y, value) {
+            Removal::Replaced(replacement) => {
+                self.root = replacement;
+                true
+            }
+            Removal::NotFound => false,
+        }
+    }
+
+    /// Removes all values and returns how many of them were found.
+    pub fn remove_all(&mut self, values: impl IntoIterator<Item = (f64, f64, T)>) -> usize {
+        values
+            .into_iter()
+            .filter(|(x, y, value)| self.remove(*x, *y, value))
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{LeafBucket, Node};
+
+    type Quadtree = super::Quadtree<(), usize>;
+
+    #[test]
+    fn removes_the_only_point() {
+        let mut q = Quadtree::default();
+        q.insert(1.0, 1.0, 0);
+        assert!(!q.remove(1.0, 1.0, &1));
+        assert!(!q.remove(2.0, 1.0, &0));
+        assert!(q.remove(1.0, 1.0, &0));
+        assert!(q.root().is_none());
+        assert_eq!(q.arena.len(), 0);
+    }
+
+    #[test]
+    fn collapses_internal_nodes_with_a_single_leaf() {
+        let mut q = Quadtree::with_extent([0., 0.], [1., 1.]);
+        q.add_all([(0.0, 0.0, 0), (0.9, 0.9, 1), (0.4, 0.4, 2)]);
+
+        assert!(q.remove(0.9, 0.9, &1));
+        assert!(matches!(
+            q.children_at(&[]).unwrap()[0].unwrap(),
+            Node::Internal { .. }
+        ));
+
+        // Removing the second to last point collapses all internal nodes above the last one.
+        assert!(q.remove(0.4, 0.4, &2));
+        assert!(matches!(
+            q.root().unwrap(),
+            Node::Leaf { x: 0.0, y: 0.0, .. }
+        ));
+        assert_eq!(q.arena.len(), 1);
+        assert_eq!(q.find(1.0, 1.0, f64::INFINITY), Some((0.0, 0.0, &0)));
+    }
+
+    #[test]
+    fn unlinks_coincident_points() {
+        let mut q = Quadtree::with_extent([0., 0.], [1., 1.]);
+        q.add_all([(0.0, 0.0, 0), (1.0, 1.0, 1), (1.0, 1.0, 2), (1.0, 1.0, 3)]);
+
+        assert!(q.remove(1.0, 1.0, &2));
+        let leaf = q.children_at(&[]).unwrap()[3].unwrap().bucket();
+        assert_eq!(leaf.unwrap().iter().collect::<Vec<_>>(), [&1, &3]);
+
+        assert!(q.remove(1.0, 1.0, &1));
+        let leaf = q.children_at(&[]).unwrap()[3].unwrap().bucket();
+        assert_eq!(leaf.unwrap().iter().collect::<Vec<_>>(), [&3]);
+    }
+
+    #[test]
+    fn removes_all_points_and_reuses_slots() {
+        let points = (0..50)
+            .map(|i| ((i % 7) as f64, (i / 7) as f64, i))
+            .collect::<Vec<_>>();
+        let mut q = Quadtree::default();
+        q.add_all(points.iter().copied());
+        let slots = q.arena.len();
+
+        assert_eq!(q.remove_all(points.iter().copied().skip(10)), 40);
+        assert!(q.arena.len() < slots);
+        for &(x, y, i) in &points[..10] {
+            assert_eq!(q.find(x, y, 0.1), Some((x, y, &i)));
+        }
+
+        q.add_all(points.iter().copied().skip(10));
+        assert_eq!(q.arena.len(), slots);
+        assert_eq!(q.remove_all(points.iter().copied()), 50);
+        assert!(q.root().is_none());
+    }
+
+    #[test]
+    fn removes_merged_points_by_their_own_position() {
+        // The x-axis of this extent can't be split, so both points share the leaf at `x = 1`.
+        let mut q = Quadtree::default();
+        (q.x0, q.y0, q.x1, q.y1) = (1.0, 0.0, 1.0 + f64::EPSILON, 1.0);
+        let data = LeafBucket {
+            first: 0,
+            others: vec![1],
+        };
+        q.root = Some(q.arena.insert(Node::Leaf {
+            x: 1.0,
+            y: 0.5,
+            data,
+        }));
+
+        assert!(!q.remove(1.0, 0.25, &1));
+        assert!(q.remove(1.0 + f64::EPSILON, 0.5, &1));
+        assert_eq!(q.data().collect::<Vec<_>>(), [(1.0, 0.5, &0)]);
+    }
+}
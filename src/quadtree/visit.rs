@@ -1,7 +1,6 @@
-use super::{
-    quad::{Quad, QuadInner},
-    Node, Quadtree,
-};
+use crate::extent::Extent;
+
+use super::{quad::Quad, Node, Quadtree};
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Visit {
@@ -32,40 +31,31 @@ impl<Q: Default, T> Quadtree<Q, T> {
     where
         F: FnMut(Quad<'_, Q, T>) -> Visit,
     {
-        let Some(root) = self.root.as_ref() else {
+        let Some(root) = self.root else {
             return;
         };
 
-        let mut stack = vec![QuadInner {
-            extent: [
-                [self.x0 as f64, self.y0 as f64],
-                [self.x1 as f64, self.y1 as f64],
-            ]
-            .into(),
-            node: root,
-        }];
+        let mut stack = vec![(root, Extent::from([self.x0, self.y0, self.x1, self.y1]))];
 
-        while let Some(q) = stack.pop() {
-            let extent = q.extent;
-            if callback(Quad::from_quad(&mut self.store, &q)) == Visit::Continue {
+        while let Some((id, extent)) = stack.pop() {
+            if callback(Quad::new(&mut self.arena, id, extent)) == Visit::Continue {
                 if let Node::Internal {
                     children: [c0, c1, c2, c3],
-                    ..
-                } = q.node
+                } = self.arena.get(id)
                 {
                     let xm = (extent.x0 + extent.x1) / 2.0;
                     let ym = (extent.y0 + extent.y1) / 2.0;
-                    if let Some(node) = c3.as_ref() {
-                        stack.push(QuadInner::new(node, xm, ym, extent.x1, extent.y1));
+                    if let Some(node) = *c3 {
+                        stack.push((node, [xm, ym, extent.x1, extent.y1].into()));
                     }
-                    if let Some(node) = c2.as_ref() {
-                        stack.push(QuadInner::new(node, extent.x0, ym, xm, extent.y1));
+                    if let Some(node) = *c2 {
+                        stack.push((node, [extent.x0, ym, xm, extent.y1].into()));
                     }
-                    if let Some(node) = c1.as_ref() {
-                        stack.push(QuadInner::new(node, xm, extent.y0, extent.x1, ym));
+                    if let Some(node) = *c1 {
+                        stack.push((node, [xm, extent.y0, extent.x1, ym].into()));
                     }
-                    if let Some(node) = c0.as_ref() {
-                        stack.push(QuadInner::new(node, extent.x0, extent.y0, xm, ym));
+                    if let Some(node) = *c0 {
+                        stack.push((node, [extent.x0, extent.y0, xm, ym].into()));
                     }
                 }
             }
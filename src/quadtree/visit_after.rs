@@ -1,64 +1,43 @@
-use super::{
-    quad::{Quad, QuadInner},
-    Node, Quadtree,
-};
+use crate::extent::Extent;
+
+use super::{quad::Quad, Node, Quadtree};
 
 impl<Q: Default, T> Quadtree<Q, T> {
     pub fn visit_after<F>(&mut self, mut callback: F)
     where
         F: FnMut(Quad<'_, Q, T>),
     {
-        let Some(root) = self.root.as_ref() else {
+        let Some(root) = self.root else {
             return;
         };
 
-        let mut quads = vec![QuadInner {
-            extent: [
-                [self.x0 as f64, self.y0 as f64],
-                [self.x1 as f64, self.y1 as f64],
-            ]
-            .into(),
-            node: root,
-        }];
+        let mut quads = vec![(root, Extent::from([self.x0, self.y0, self.x1, self.y1]))];
         let mut next = Vec::new();
 
-        while let Some(q) = quads.pop() {
+        while let Some((id, extent)) = quads.pop() {
             if let Node::Internal {
                 children: [c0, c1, c2, c3],
-                ..
-            } = q.node
+            } = self.arena.get(id)
             {
-                let xm = (q.extent.x0 + q.extent.x1) / 2.0;
-                let ym = (q.extent.y0 + q.extent.y1) / 2.0;
-                if let Some(node) = c0.as_ref() {
-                    quads.push(QuadInner {
-                        extent: [q.extent.x0, q.extent.y0, xm, ym].into(),
-                        node,
-                    });
+                let xm = (extent.x0 + extent.x1) / 2.0;
+                let ym = (extent.y0 + extent.y1) / 2.0;
+                if let Some(node) = *c0 {
+                    quads.push((node, [extent.x0, extent.y0, xm, ym].into()));
                 }
-                if let Some(node) = c1.as_ref() {
-                    quads.push(QuadInner {
-                        extent: [xm, q.extent.y0, q.extent.x1, ym].into(),
-                        node,
-                    });
+                if let Some(node) = *c1 {
+                    quads.push((node, [xm, extent.y0, extent.x1, ym].into()));
                 }
-                if let Some(node) = c2.as_ref() {
-                    quads.push(QuadInner {
-                        extent: [q.extent.x0, ym, xm, q.extent.y1].into(),
-                        node,
-                    });
+                if let Some(node) = *c2 {
+                    quads.push((node, [extent.x0, ym, xm, extent.y1].into()));
                 }
-                if let Some(node) = c3.as_ref() {
-                    quads.push(QuadInner {
-                        extent: [xm, ym, q.extent.x1, q.extent.y1].into(),
-                        node,
-                    });
+                if let Some(node) = *c3 {
+                    quads.push((node, [xm, ym, extent.x1, extent.y1].into()));
                 }
             }
-            next.push(q);
+            next.push((id, extent));
         }
-        while let Some(q) = next.pop() {
-            callback(Quad::from_quad(&mut self.store, &q));
+        while let Some((id, extent)) = next.pop() {
+            callback(Quad::new(&mut self.arena, id, extent));
         }
     }
 }
@@ -160,6 +139,6 @@ mod test {
             };
             *quad.value_mut() = r;
         });
-        assert_eq!(*q.store.get(q.root().unwrap().handle()), n);
+        assert_eq!(*q.arena.value(q.root.unwrap()), n);
     }
 }